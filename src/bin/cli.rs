@@ -2,12 +2,16 @@
 
 use std::{fs, path::Path};
 
-use clap::{Arg, Command};
+use clap::{Arg, ArgAction, Command};
 
 use getheode::{
     GETHEODE_VERSION,
     error::*,
-    phonology::{rule::PhonoRuleSet, string::PhonoString},
+    phonology::{
+        rule::{GeometryCheck, PhonoRuleParseOpts, PhonoRuleSet, Resyllabify},
+        segment::{Notation, SegmentTable},
+        string::{PhonoString, WeightOpts},
+    },
 };
 
 fn file_or_raw(input: &str) -> Result<String> {
@@ -41,6 +45,46 @@ fn cli() -> Command {
                         .value_name("INPUT")
                         .required(true)
                         .help("phonological string or file"),
+                )
+                .arg(
+                    Arg::new("trace")
+                        .short('t')
+                        .long("trace")
+                        .action(ArgAction::SetTrue)
+                        .help("print the derivation table, with the rules that applied"),
+                )
+                .arg(
+                    Arg::new("resyllabify")
+                        .long("resyllabify")
+                        .value_name("WHEN")
+                        .value_parser(["rule", "end"])
+                        .help("rebuild the syllables after each rule, or at the end"),
+                )
+                .arg(
+                    Arg::new("geometry")
+                        .long("geometry")
+                        .value_name("CHECK")
+                        .value_parser(["normalize", "validate"])
+                        .help("normalize the output of each rule, or fail on contradictions"),
+                )
+                .arg(
+                    Arg::new("light-codas")
+                        .long("light-codas")
+                        .action(ArgAction::SetTrue)
+                        .help("don't count codas as morae in syllable weights"),
+                )
+                .arg(
+                    Arg::new("segments")
+                        .short('s')
+                        .long("segments")
+                        .value_name("CSV")
+                        .help("feature CSV file of extra segment symbols"),
+                )
+                .arg(
+                    Arg::new("xsampa")
+                        .long("xsampa")
+                        .action(ArgAction::SetTrue)
+                        .help("print the output in X-SAMPA"),
                 ),
         )
 }
@@ -61,20 +105,67 @@ fn main() {
                     std::process::exit(1);
                 });
 
-            let opts = Default::default();
-            let rule_set = PhonoRuleSet::parse(rule_str.trim(), opts).unwrap_or_else(|e| {
-                eprintln!("Error parsing rule \"{rule_str}\"\n{e}");
+            if let Some(segments) = args.get_one::<String>("segments") {
+                let mut table = SegmentTable::builtin();
+                fs::read_to_string(segments)
+                    .map_err(Error::from)
+                    .and_then(|csv| table.extend_from_csv(&csv))
+                    .unwrap_or_else(|e| {
+                        eprintln!("Error loading segments \"{segments}\"\n{e}");
+                        std::process::exit(1);
+                    });
+                table.install();
+            }
+
+            let resyllabify = match args.get_one::<String>("resyllabify").map(String::as_str) {
+                Some("rule") => Resyllabify::AfterEachRule,
+                Some("end") => Resyllabify::AtEnd,
+                _ => Resyllabify::Never,
+            };
+            let geometry = match args.get_one::<String>("geometry").map(String::as_str) {
+                Some("normalize") => GeometryCheck::Normalize,
+                Some("validate") => GeometryCheck::Validate,
+                _ => GeometryCheck::Off,
+            };
+            let weight = WeightOpts {
+                moraic_codas: !args.get_flag("light-codas"),
+            };
+            let opts = PhonoRuleParseOpts {
+                resyllabify,
+                geometry,
+                weight,
+                ..Default::default()
+            };
+            let rule_set = PhonoRuleSet::parse(&rule_str, opts).unwrap_or_else(|e| {
+                eprintln!("Error parsing rules\n{e}");
                 std::process::exit(1);
             });
-            let (rem, string) = PhonoString::parse(input_str.trim()).unwrap_or_else(|e| {
+            let string: PhonoString = input_str.trim().parse().unwrap_or_else(|e| {
                 eprintln!("Error parsing input \"{input_str}\"\n{e}");
                 std::process::exit(1);
             });
-            if !rem.is_empty() {
-                eprintln!("Error parsing input. Remainder=\"{rem}\"");
-            }
 
-            println!("{}", rule_set.apply(string));
+            let notation = if args.get_flag("xsampa") {
+                Notation::XSampa
+            } else {
+                Notation::Ipa
+            };
+            let output = if args.get_flag("trace") {
+                rule_set
+                    .derive(string)
+                    .map(|derivation| derivation.format_table_in(notation))
+            } else {
+                rule_set
+                    .apply(string)
+                    .map(|string| format!("{}\n", string.format_in(notation)))
+            };
+            match output {
+                Ok(output) => print!("{output}"),
+                Err(e) => {
+                    eprintln!("Error applying rules\n{e}");
+                    std::process::exit(1);
+                }
+            }
         }
         _ => unreachable!(),
     }
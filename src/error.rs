@@ -1,10 +1,18 @@
-use std::fmt;
+use std::{fmt, ops::Range};
 
 pub type Result<T> = core::result::Result<T, Error>;
 
 #[derive(Debug)]
 pub enum Error {
-    Other(String), // most errors in the getheode engine are this
+    /// text that isn't a valid segment or phonological string
+    Segment(SpannedMessage),
+    /// text that isn't a valid rule
+    RuleSyntax(SpannedMessage),
+    /// a rule with tags or variables that can't be matched between its input and output
+    Tag(SpannedMessage),
+    /// an invalid operation on a tree, like replacing an empty range
+    Tree(String),
+    Other(String),
     Io(std::io::Error),
 }
 impl Error {
@@ -14,11 +22,48 @@ impl Error {
     {
         Self::Other(text.to_string())
     }
+
+    pub fn segment<T: fmt::Display>(text: T, span: Option<Span>) -> Error {
+        Self::Segment(SpannedMessage::new(text, span))
+    }
+
+    pub fn rule_syntax<T: fmt::Display>(text: T, span: Option<Span>) -> Error {
+        Self::RuleSyntax(SpannedMessage::new(text, span))
+    }
+
+    pub fn tag<T: fmt::Display>(text: T, span: Option<Span>) -> Error {
+        Self::Tag(SpannedMessage::new(text, span))
+    }
+
+    pub fn tree<T: fmt::Display>(text: T) -> Error {
+        Self::Tree(text.to_string())
+    }
+
+    /// The span of the text the error is about, if known
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            Error::Segment(m) | Error::RuleSyntax(m) | Error::Tag(m) => m.span.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Set the span of the error if it doesn't have one. The span is usually the whole text that
+    /// was being parsed, when the part causing the error isn't known.
+    pub fn or_span(mut self, span: Span) -> Self {
+        if let Error::Segment(m) | Error::RuleSyntax(m) | Error::Tag(m) = &mut self {
+            m.span.get_or_insert(span);
+        }
+        self
+    }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Error::Segment(m) => write!(f, "Getheode: invalid segment{m}"),
+            Error::RuleSyntax(m) => write!(f, "Getheode: invalid rule{m}"),
+            Error::Tag(m) => write!(f, "Getheode: invalid tags{m}"),
+            Error::Tree(text) => write!(f, "Getheode: invalid tree operation: {text}"),
             Error::Other(text) => write!(f, "Getheode: {text}"),
             Error::Io(error) => error.fmt(f),
         }
@@ -28,8 +73,8 @@ impl fmt::Display for Error {
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            Error::Other(_) => None,
             Error::Io(error) => Some(error),
+            _ => None,
         }
     }
 }
@@ -39,3 +84,108 @@ impl From<std::io::Error> for Error {
         Self::Io(e)
     }
 }
+
+/// An error message, with the text it is about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpannedMessage {
+    pub message: String,
+    pub span: Option<Span>,
+}
+
+impl SpannedMessage {
+    pub fn new<T: fmt::Display>(message: T, span: Option<Span>) -> Self {
+        Self {
+            message: message.to_string(),
+            span,
+        }
+    }
+}
+
+impl fmt::Display for SpannedMessage {
+    /// formats as a suffix of the error kind: `at line 1, column 3: message`, and the caret
+    /// diagram on the next lines
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.span {
+            Some(span) => write!(
+                f,
+                " at line {}, column {}: {}\n{}",
+                span.line,
+                span.column,
+                self.message,
+                span.caret_diagram()
+            ),
+            None => write!(f, ": {}", self.message),
+        }
+    }
+}
+
+/// A part of a source text, like a rule file, that an error is about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    /// byte range in the source text
+    pub range: Range<usize>,
+    /// line of the start of the span, starting at 1
+    pub line: usize,
+    /// column of the start of the span in characters, starting at 1
+    pub column: usize,
+    /// the source line containing the start of the span
+    pub line_text: String,
+}
+
+impl Span {
+    /// Span of the bytes in `range` of `source`
+    pub fn new(source: &str, range: Range<usize>) -> Self {
+        let start = range.start.min(source.len());
+        let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[start..]
+            .find('\n')
+            .map_or(source.len(), |i| start + i);
+        let line = source[..start].matches('\n').count() + 1;
+        let column = source[line_start..start].chars().count() + 1;
+
+        Self {
+            range,
+            line,
+            column,
+            line_text: source[line_start..line_end].to_string(),
+        }
+    }
+
+    /// Span of `part` in `source`. `part` must be a slice of `source`, like the remainder of a
+    /// parser, otherwise the span is the whole source.
+    pub fn of(source: &str, part: &str) -> Self {
+        let offset = (part.as_ptr() as usize).wrapping_sub(source.as_ptr() as usize);
+        if offset > source.len() || offset + part.len() > source.len() {
+            return Self::new(source, 0..source.len());
+        }
+        Self::new(source, offset..(offset + part.len()))
+    }
+
+    /// The line of the span, with carets under the spanned text
+    ///
+    /// ```text
+    ///   |
+    /// 2 | t -> d / V_%
+    ///   |            ^
+    /// ```
+    pub fn caret_diagram(&self) -> String {
+        let line_number = self.line.to_string();
+        let margin = " ".repeat(line_number.len());
+        let line_len = self.line_text.chars().count();
+        let caret_start = (self.column - 1).min(line_len);
+        // one caret per character of the span, stopping at the end of the line
+        let rest: String = self.line_text.chars().skip(caret_start).collect();
+        let caret_len = rest
+            .char_indices()
+            .take_while(|(i, _)| *i < self.range.len())
+            .count()
+            .max(1);
+
+        format!(
+            "{margin} |\n{line_number} | {}\n{margin} | {}{}",
+            self.line_text,
+            " ".repeat(caret_start),
+            "^".repeat(caret_len)
+        )
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 #[derive(Debug, Clone, Copy, Default, Eq, Hash, PartialEq)]
 pub enum FeatureState {
     #[default]
@@ -5,7 +7,77 @@ pub enum FeatureState {
     POS, // (+) present in the segment
     NEG, // (-) not present in the segment
     NA,  // not applicable, could be either positive or negative for this segment, we don't care
+    // (α) rules only: binds to `POS` or `NEG`, same value everywhere in the rule
+    ALPHA(Variable),
+    // (-α) rules only: the opposite of the value bound to the variable
+    NALPHA(Variable),
 }
 
 // useful for both syllables and segments
 pub type Feature = u8;
+
+/// An alpha-notation variable in a rule, `α` is 0, `β` is 1, etc.
+pub type Variable = u8;
+
+/// Values bound to alpha-notation variables while matching a rule
+pub type VariableBindings = HashMap<Variable, FeatureState>;
+
+/// Greek letters used to write variables. Variables can also be written `@a`, `@b`, etc.
+pub const GREEK_VARIABLES: [char; 4] = ['α', 'β', 'γ', 'δ'];
+
+impl FeatureState {
+    /// `POS` becomes `NEG`, `α` becomes `-α` and vice versa. Other states are unchanged.
+    pub fn negate(self) -> Self {
+        match self {
+            FeatureState::POS => FeatureState::NEG,
+            FeatureState::NEG => FeatureState::POS,
+            FeatureState::ALPHA(v) => FeatureState::NALPHA(v),
+            FeatureState::NALPHA(v) => FeatureState::ALPHA(v),
+            state => state,
+        }
+    }
+
+    pub fn is_variable(self) -> bool {
+        self.variable().is_some()
+    }
+
+    /// The variable of `α` or `-α`, `None` for other states
+    pub fn variable(self) -> Option<Variable> {
+        match self {
+            FeatureState::ALPHA(v) | FeatureState::NALPHA(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Bind the variable in `self` (a pattern state) to `value` (a matched state).
+    /// Returns false if `value` isn't binary, or if it contradicts an existing binding.
+    /// Does nothing if `self` isn't a variable.
+    pub fn bind(self, value: FeatureState, bindings: &mut VariableBindings) -> bool {
+        let (var, value) = match self {
+            FeatureState::ALPHA(v) => (v, value),
+            FeatureState::NALPHA(v) => (v, value.negate()),
+            _ => return true,
+        };
+        if !matches!(value, FeatureState::POS | FeatureState::NEG) {
+            return false;
+        }
+        *bindings.entry(var).or_insert(value) == value
+    }
+
+    /// Replace a variable by its bound value. Returns None if the variable isn't bound.
+    pub fn substitute(self, bindings: &VariableBindings) -> Option<Self> {
+        match self {
+            FeatureState::ALPHA(v) => bindings.get(&v).copied(),
+            FeatureState::NALPHA(v) => bindings.get(&v).map(|s| s.negate()),
+            state => Some(state),
+        }
+    }
+}
+
+/// Format a variable as written in rules: `α`, or `@e` when there is no greek letter for it
+pub fn format_variable(var: Variable) -> String {
+    match GREEK_VARIABLES.get(var as usize) {
+        Some(c) => c.to_string(),
+        None => format!("@{}", (b'a' + var) as char),
+    }
+}
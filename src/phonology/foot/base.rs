@@ -0,0 +1,121 @@
+use std::{fmt, ops::Add};
+
+use nom::{
+    IResult, Parser,
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::space0,
+    combinator::value,
+    error::{Error, ErrorKind},
+    multi::separated_list1,
+    sequence::delimited,
+};
+
+use crate::phonology::{
+    feature::FeatureState::{self, *},
+    foot::{FOOT_FEATURE_COUNT, FOOT_FEATURE_NAMES},
+};
+
+/// The symbol of feet in rules and strings, before a foot feature set: `Σ[+trochaic]`
+pub const FOOT_SYMBOL: char = 'Σ';
+
+/// The boundary between two feet of a word, in rules and strings
+pub const FOOT_BOUNDARY: &str = "|";
+
+/// The boundary between two phrases, in rules and strings. `||` also works.
+pub const PHRASE_BOUNDARY: &str = "‖";
+
+/// set of features a foot can have
+#[derive(Debug, Clone, PartialEq)]
+pub struct FootFeatures {
+    pub features: [FeatureState; FOOT_FEATURE_COUNT as usize],
+}
+
+impl FootFeatures {
+    pub const fn new(features: [FeatureState; FOOT_FEATURE_COUNT as usize]) -> Self {
+        Self { features }
+    }
+
+    /// construct a foot feature set with all features undefined
+    pub const fn new_undef() -> Self {
+        Self {
+            features: [UNDEF; FOOT_FEATURE_COUNT as usize],
+        }
+    }
+
+    /// returns true if this foot matches `pattern`'s defined features
+    pub fn matches(&self, pattern: &FootFeatures) -> bool {
+        pattern
+            .features
+            .iter()
+            .zip(&self.features)
+            .all(|(p, f)| *p == UNDEF || p == f)
+    }
+
+    /// true if no feature is defined
+    pub fn is_undef(&self) -> bool {
+        self.features.iter().all(|f| *f == UNDEF)
+    }
+}
+
+impl Default for FootFeatures {
+    fn default() -> Self {
+        Self::new_undef()
+    }
+}
+
+impl Add<FootFeatures> for FootFeatures {
+    type Output = Self;
+
+    /// adds the features of the rhs foot to the lhs foot: the features defined in the rhs are
+    /// overwritten in the result.
+    fn add(mut self, rhs: Self) -> Self {
+        for (feature, rhs_feature) in self.features.iter_mut().zip(rhs.features) {
+            if rhs_feature != UNDEF {
+                *feature = rhs_feature;
+            }
+        }
+        self
+    }
+}
+
+impl fmt::Display for FootFeatures {
+    /// the defined features, like "+trochaic"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, feature) in FOOT_FEATURE_NAMES.iter().zip(self.features) {
+            match feature {
+                POS => write!(f, "+{name}")?,
+                NEG => write!(f, "-{name}")?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// parse a foot feature set after the foot symbol, like `Σ[+trochaic]`. Foot features are `+` or
+/// `-`, not variables.
+pub fn parse_foot_features(input: &str) -> IResult<&str, FootFeatures> {
+    let symbol = FOOT_SYMBOL.encode_utf8(&mut [0; 4]).to_string();
+    let value = alt((value(POS, tag("+")), value(NEG, tag("-"))));
+    let features = separated_list1(space0, (value, parse_foot_feature_name));
+    let mut parser = (
+        tag(symbol.as_str()),
+        delimited(tag("["), features, tag("]")),
+    );
+
+    let (rest, (_, features)) = parser.parse(input)?;
+    let mut foot = FootFeatures::new_undef();
+    for (state, feature) in features {
+        foot.features[feature] = state;
+    }
+    Ok((rest, foot))
+}
+
+fn parse_foot_feature_name(input: &str) -> IResult<&str, usize> {
+    FOOT_FEATURE_NAMES
+        .iter()
+        .position(|name| input.starts_with(name))
+        .map(|i| (&input[FOOT_FEATURE_NAMES[i].len()..], i))
+        .ok_or(nom::Err::Error(Error::new(input, ErrorKind::Tag)))
+}
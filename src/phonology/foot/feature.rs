@@ -0,0 +1,7 @@
+// The features that a foot can have
+pub const FOOT_FEATURE_COUNT: u8 = 1;
+// foot features : [trochaic]
+// the head of a [+trochaic] foot is its first syllable, like in a trochee. iambs are [-trochaic]
+pub const TROCHAIC: u8 = 0;
+
+pub const FOOT_FEATURE_NAMES: [&str; FOOT_FEATURE_COUNT as usize] = ["trochaic"];
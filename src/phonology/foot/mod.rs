@@ -0,0 +1,5 @@
+mod base;
+mod feature;
+
+pub use base::*;
+pub use feature::*;
@@ -1,4 +1,5 @@
 pub mod feature;
+pub mod foot;
 pub mod rule;
 pub mod segment;
 pub mod string;
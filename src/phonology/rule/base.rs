@@ -1,38 +1,88 @@
-use std::collections::{HashMap, HashSet};
-
-use crate::{
-    d3tree,
-    phonology::{
-        rule::{PatternMatch, PhonoStringPattern},
-        segment::SegmentFeatures,
-        string::PhonoString,
-        syllable::SyllableFeatures,
-        tree::{Depth3Tree, iter::IterDepth0},
-    },
+use std::{
+    collections::{HashMap, HashSet},
+    ops::{Range, RangeInclusive},
 };
 
+use crate::phonology::{
+    feature::VariableBindings,
+    foot::FootFeatures,
+    rule::{ApplicationMode, BoundaryLevel, PatternBorder, PatternMatch, PhonoStringPattern},
+    segment::SegmentFeatures,
+    string::{Constituent, PhonoString, Weight, WeightOpts},
+    syllable::SyllableFeatures,
+    tree::{Attach, Depth3Tree, Depth5Tree},
+};
+
+/// The prosodic tree of a tagged phonological string
+pub type TaggedProsodicTree = Depth5Tree<(), (), FootFeatures, SyllableInfo, SegmentInfo>;
+
 /// A phonological string, where syllable or segment nodes may be tagged
 #[derive(Debug)]
-pub struct TaggedPhonoString(Depth3Tree<(), SyllableInfo, SegmentInfo>);
+pub struct TaggedPhonoString(TaggedProsodicTree);
 impl TaggedPhonoString {
-    pub(crate) fn new(tree: Depth3Tree<(), SyllableInfo, SegmentInfo>) -> Self {
+    /// A string of a single phrase from a tree of words, syllables and segments. Each word is a
+    /// single foot without features.
+    pub fn new(tree: Depth3Tree<(), SyllableInfo, SegmentInfo>) -> Self {
+        Self(tree.into())
+    }
+
+    pub(crate) fn from_tree(tree: TaggedProsodicTree) -> Self {
         Self(tree)
     }
 
-    pub fn words(&self) -> &[()] {
+    pub fn phrases(&self) -> &[()] {
         self.0.layer_0()
     }
 
-    pub fn syls(&self) -> &[(SyllableInfo, usize)] {
+    pub fn words(&self) -> &[((), usize)] {
         self.0.layer_1()
     }
 
-    pub fn segs(&self) -> &[(SegmentInfo, usize)] {
+    pub fn feet(&self) -> &[(FootFeatures, usize)] {
         self.0.layer_2()
     }
 
-    pub fn iter<'a>(&'a self) -> IterDepth0<'a, (), SyllableInfo, SegmentInfo> {
-        self.0.iter()
+    pub fn syls(&self) -> &[(SyllableInfo, usize)] {
+        self.0.layer_3()
+    }
+
+    pub fn segs(&self) -> &[(SegmentInfo, usize)] {
+        self.0.layer_4()
+    }
+
+    /// The string without its tags
+    pub fn untagged(&self) -> PhonoString {
+        let tree = self.0.try_map(
+            |_| Some(()),
+            |_| Some(()),
+            |foot| Some(foot.clone()),
+            |syl| Some(syl.features.clone()),
+            |seg| Some(seg.features.clone()),
+        );
+        PhonoString::from_tree(tree.expect("untagging a node never fails"))
+    }
+
+    /// true if the syllable `syl_idx` stands for a whole syllable: it is written with the
+    /// syllable symbol, like `σ[+stress]`, and has no segments
+    pub fn is_whole_syllable(&self, syl_idx: usize) -> bool {
+        self.0.layer_3()[syl_idx].0.explicit && self.0.children_4(syl_idx).is_empty()
+    }
+
+    /// true if `tag` is the tag of a whole syllable (see `is_whole_syllable`)
+    pub fn is_whole_syllable_tag(&self, tag: Option<u32>) -> bool {
+        tag.is_some()
+            && (0..self.0.len_3()).any(|syl_idx| {
+                self.0.layer_3()[syl_idx].0.tag == tag && self.is_whole_syllable(syl_idx)
+            })
+    }
+
+    /// The number of units of the string: its segments and its whole syllables (see
+    /// `is_whole_syllable`)
+    pub fn unit_count(&self) -> usize {
+        let whole_syls = (0..self.0.len_3())
+            .filter(|syl_idx| self.is_whole_syllable(*syl_idx))
+            .count();
+        self.0.len_4() + whole_syls
     }
 
     pub fn pretty_format(&self) -> String {
@@ -40,29 +90,74 @@ impl TaggedPhonoString {
     }
 }
 
+/// Where the nodes of a pattern are in the hay, for a match
+#[derive(Debug, Default)]
+struct Alignment {
+    /// the hay syllable of each pattern syllable
+    syls: Vec<usize>,
+    /// the hay segment of each pattern segment
+    segs: Vec<usize>,
+    /// the hay segment at the start of each unit of the pattern (see
+    /// `TaggedPhonoString::unit_count`), then the end of the match
+    units: Vec<usize>,
+}
+
 /// A pattern to match in phonological strings
 #[derive(Debug)]
 pub struct PhonoRule {
     // use a tree to represent the string, like phonological strings
     pub pattern: PhonoStringPattern,
     pub replace_tree: TaggedPhonoString,
+    /// range of the pattern's units (segments and whole syllables, see
+    /// `TaggedPhonoString::unit_count`) replaced by `replace_tree`, the rest of the pattern is
+    /// context. an empty focus makes an insertion rule
+    pub focus: Range<usize>,
+    /// for insertion rules, the syllable the inserted segments go into
+    pub attach: Attach,
+    pub mode: ApplicationMode,
+    /// how the weights of syllables are counted, for patterns like `σ[+heavy]`
+    pub weight: WeightOpts,
+    /// where the rule was written, for rules read from a rule file
+    pub source: Option<RuleSource>,
+}
+
+/// The line of a rule file a rule comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleSource {
+    /// line number, starting at 1
+    pub line: usize,
+    /// text of the rule, without comments
+    pub text: String,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct SyllableInfo {
     pub tag: Option<u32>,
     pub features: SyllableFeatures,
+    /// in patterns, the weights the syllable may have, like `Heavy..=Superheavy` for
+    /// `σ[+heavy]`
+    pub weight: Option<RangeInclusive<Weight>>,
+    /// written with the syllable symbol, like `σ[+stress]`. Such a syllable without segments
+    /// stands for a whole syllable (see `TaggedPhonoString::is_whole_syllable`).
+    pub explicit: bool,
 }
 
 impl SyllableInfo {
     pub const fn new(id: Option<u32>, features: SyllableFeatures) -> Self {
-        Self { tag: id, features }
+        Self {
+            tag: id,
+            features,
+            weight: None,
+            explicit: false,
+        }
     }
 
     pub const fn new_tagged(id: u32, features: SyllableFeatures) -> Self {
         Self {
             tag: Some(id),
             features,
+            weight: None,
+            explicit: false,
         }
     }
 
@@ -70,6 +165,8 @@ impl SyllableInfo {
         Self {
             tag: None,
             features,
+            weight: None,
+            explicit: false,
         }
     }
 }
@@ -78,160 +175,274 @@ impl SyllableInfo {
 pub struct SegmentInfo {
     pub tag: Option<u32>,
     pub features: SegmentFeatures,
+    /// in patterns, the constituent of its syllable the segment must be in (or not in, if
+    /// false), like `[+coda]`
+    pub position: Option<(Constituent, bool)>,
 }
 
 impl SegmentInfo {
     pub const fn new(id: Option<u32>, features: SegmentFeatures) -> Self {
-        Self { tag: id, features }
+        Self {
+            tag: id,
+            features,
+            position: None,
+        }
     }
     pub const fn new_tagged(id: u32, features: SegmentFeatures) -> Self {
         Self {
             tag: Some(id),
             features,
+            position: None,
         }
     }
     pub const fn new_untagged(features: SegmentFeatures) -> Self {
         Self {
             tag: None,
             features,
+            position: None,
         }
     }
 }
 
 impl PhonoRule {
+    /// A rule that replaces the whole pattern by `replace_tree`
     pub fn new(pattern: PhonoStringPattern, replace_tree: TaggedPhonoString) -> Self {
+        let focus = 0..pattern.tree.unit_count();
         Self {
             pattern,
             replace_tree,
+            focus,
+            attach: Attach::default(),
+            mode: ApplicationMode::default(),
+            weight: WeightOpts::default(),
+            source: None,
         }
     }
 
     pub fn find(&self, hay: PhonoString) -> Vec<PatternMatch> {
-        let hay_seg_n = hay.tree.len_2();
-        let hay_syl_n = hay.tree.len_1();
-        let hay_word_n = hay.tree.len_0();
+        let hay_seg_n = hay.tree.len_4();
+        let hay_syl_n = hay.tree.len_3();
+        let hay_word_n = hay.tree.len_1();
         let match_seg_n = self.pattern.tree.segs().len();
         let match_syl_n = self.pattern.tree.syls().len();
         let match_word_n = self.pattern.tree.words().len();
 
-        if hay_seg_n < match_seg_n || hay_syl_n < match_syl_n || hay_word_n < match_word_n {
-            return vec![];
-        }
-
-        (0..=(hay_seg_n - match_seg_n))
-            .filter_map(|seg_offset| self.match_at(&hay, seg_offset))
+        let matches: Vec<PatternMatch> = if self.pattern.tree.unit_count() == 0 {
+            // only boundaries to match: matches are the gaps between segments
+            (0..=hay_seg_n)
+                .filter_map(|gap| self.match_gap(&hay, gap))
+                .collect()
+        } else if hay_seg_n < match_seg_n || hay_syl_n < match_syl_n || hay_word_n < match_word_n {
+            vec![]
+        } else {
+            (0..=(hay_seg_n - match_seg_n))
+                .filter_map(|seg_offset| self.match_at(&hay, seg_offset))
+                .collect()
+        };
+
+        // a boundary change needs segments on both sides: the edges of the string stay
+        matches
+            .into_iter()
+            .filter(|m| self.attach != Attach::Both || (1..hay_seg_n).contains(&m.range.start))
             .collect()
     }
 
     fn match_at(&self, hay: &PhonoString, seg_offset: usize) -> Option<PatternMatch> {
-        let hay_segs = hay.tree.layer_2();
-        let hay_syls = hay.tree.layer_1();
-        let pat_segs = self.pattern.tree.segs();
-        let pat_syls = self.pattern.tree.syls();
-
-        // syl_offset is the absolute index of the first matched syllable in the hay.
-        // the pattern stores relative parent indices (0, 1, 2...), so we subtract
-        // syl_offset when comparing to normalize hay indices to the same origin.
-        let syl_offset = hay_segs[seg_offset].1;
-        for (idx, (pat_seg, pat_syl_idx)) in pat_segs.iter().enumerate() {
-            let (hay_seg, hay_syl_idx) = &hay_segs[seg_offset + idx];
-            if *pat_syl_idx != hay_syl_idx - syl_offset {
-                return None;
-            }
-            if !hay_seg.matches(&pat_seg.features) {
+        let hay_syls = hay.tree.layer_3();
+        let hay_feet = hay.tree.layer_2();
+        let pattern = &self.pattern.tree.0;
+        let alignment = self.align(hay, seg_offset)?;
+
+        // a syllable boundary of the pattern may be a foot boundary in the hay, so a pattern foot
+        // matches the foot of each of its syllables
+        for (idx, (pat_syl, pat_foot_idx)) in pattern.layer_3().iter().enumerate() {
+            let hay_syl_idx = alignment.syls[idx];
+            let (hay_syl, hay_foot_idx) = &hay_syls[hay_syl_idx];
+            if !hay_syl.matches(&pat_syl.features) {
                 return None;
             }
-        }
-
-        // same normalization for word indices relative to word_offset
-        let word_offset = hay_syls[syl_offset].1;
-        for (idx, (pat_syl, pat_word_idx)) in pat_syls.iter().enumerate() {
-            let (hay_syl, hay_word_idx) = &hay_syls[syl_offset + idx];
-            if *pat_word_idx != hay_word_idx - word_offset {
+            if let Some(weight) = &pat_syl.weight
+                && !weight.contains(&hay.weight(hay_syl_idx, &self.weight))
+            {
                 return None;
             }
-            if !hay_syl.matches(&pat_syl.features) {
+            if !hay_feet[*hay_foot_idx]
+                .0
+                .matches(&pattern.layer_2()[*pat_foot_idx].0)
+            {
                 return None;
             }
         }
 
-        let hay_seg_n = hay_segs.len();
-        let hay_syl_n = hay_syls.len();
-        let match_seg_n = pat_segs.len();
-        let match_syl_n = pat_syls.len();
-
-        // a boundary exists when adjacent elements have different parents, or
-        // the match starts/ends at the edge of the entire string.
-        let left_syl_border = seg_offset == 0 || hay_segs[seg_offset - 1].1 != syl_offset;
-        let left_word_border = syl_offset == 0 || hay_syls[syl_offset - 1].1 != word_offset;
-        if !self
-            .pattern
-            .left_bound
-            .respects(left_syl_border, left_word_border)
+        let match_end = *alignment.units.last()?;
+        let left_border = BoundaryLevel::at_gap(&hay.tree, seg_offset);
+        let right_border = BoundaryLevel::at_gap(&hay.tree, match_end);
+        if !self.pattern.left_bound.respects(left_border)
+            || !self.pattern.right_bound.respects(right_border)
         {
             return None;
         }
 
-        let last_seg = seg_offset + match_seg_n - 1;
-        let right_syl_border =
-            last_seg + 1 == hay_seg_n || hay_segs[last_seg].1 != hay_segs[last_seg + 1].1;
-        let last_syl = syl_offset + match_syl_n - 1;
-        let right_word_border =
-            last_syl + 1 == hay_syl_n || hay_syls[last_syl].1 != hay_syls[last_syl + 1].1;
-        if !self
-            .pattern
-            .right_bound
-            .respects(right_syl_border, right_word_border)
+        let (syl_captures, seg_captures) = self.build_captures(hay, &alignment)?;
+        let bindings = self.bind_variables(hay, &alignment)?;
+
+        Some(PatternMatch {
+            range: alignment.units[self.focus.start]..alignment.units[self.focus.end],
+            replace_with: self.build_replacement(hay, &syl_captures, &seg_captures, &bindings)?,
+            attach: self.attach,
+        })
+    }
+
+    /// Align the pattern with the hay, from the segment `seg_offset`. The segments must match,
+    /// with the pattern's boundaries between them, and the whole syllables of the pattern (see
+    /// `TaggedPhonoString::is_whole_syllable`) are whole syllables of the hay. Pattern syllables
+    /// map one to one to the hay's syllables.
+    fn align(&self, hay: &PhonoString, seg_offset: usize) -> Option<Alignment> {
+        let hay_segs = hay.tree.layer_4();
+        let pattern = &self.pattern.tree.0;
+        let mut alignment = Alignment::default();
+        let mut gap = seg_offset;
+
+        for syl_idx in 0..pattern.len_3() {
+            if syl_idx > 0
+                && !PatternBorder::from(BoundaryLevel::between_syllables(
+                    pattern,
+                    syl_idx - 1,
+                    syl_idx,
+                ))
+                .respects(BoundaryLevel::at_gap(&hay.tree, gap))
+            {
+                return None;
+            }
+            let (_, hay_syl_idx) = hay_segs.get(gap)?;
+            alignment.syls.push(*hay_syl_idx);
+
+            if self.pattern.tree.is_whole_syllable(syl_idx) {
+                let hay_syl_segs = hay.tree.children_4(*hay_syl_idx);
+                if hay_syl_segs.start != gap {
+                    return None;
+                }
+                alignment.units.push(gap);
+                gap = hay_syl_segs.end;
+                continue;
+            }
+
+            let pat_segs = pattern.children_4(syl_idx);
+            for seg_idx in pat_segs.clone() {
+                let pat_seg = &pattern.layer_4()[seg_idx].0;
+                let (hay_seg, _) = hay_segs.get(gap)?;
+                if !hay_seg.matches(&pat_seg.features) {
+                    return None;
+                }
+                if let Some((constituent, is_in)) = pat_seg.position
+                    && (hay.constituent_of(gap) == Some(constituent)) != is_in
+                {
+                    return None;
+                }
+                // the segments of a pattern syllable are in a single syllable of the hay
+                if seg_idx > pat_segs.start
+                    && BoundaryLevel::at_gap(&hay.tree, gap) != BoundaryLevel::Segment
+                {
+                    return None;
+                }
+                alignment.segs.push(gap);
+                alignment.units.push(gap);
+                gap += 1;
+            }
+        }
+
+        alignment.units.push(gap);
+        Some(alignment)
+    }
+
+    /// match a pattern without segments, like the context of `∅ -> e / #_`, at the gap before
+    /// segment `gap`.
+    fn match_gap(&self, hay: &PhonoString, gap: usize) -> Option<PatternMatch> {
+        let hay_seg_n = hay.tree.len_4();
+
+        // the string's edges only count as borders on their side of the gap
+        let (left_border, right_border) = if hay_seg_n == 0 {
+            (BoundaryLevel::Phrase, BoundaryLevel::Phrase)
+        } else if gap == 0 {
+            (BoundaryLevel::Phrase, BoundaryLevel::Segment)
+        } else if gap == hay_seg_n {
+            (BoundaryLevel::Segment, BoundaryLevel::Phrase)
+        } else {
+            let border = BoundaryLevel::at_gap(&hay.tree, gap);
+            (border, border)
+        };
+        if !self.pattern.left_bound.respects(left_border)
+            || !self.pattern.right_bound.respects(right_border)
         {
             return None;
         }
 
-        let (syl_captures, seg_captures) =
-            self.build_captures(hay_segs, hay_syls, seg_offset, syl_offset)?;
-
         Some(PatternMatch {
-            range: seg_offset..(seg_offset + match_seg_n),
-            replace_with: self.build_replacement(&syl_captures, &seg_captures),
+            range: gap..gap,
+            replace_with: self.build_replacement(
+                hay,
+                &HashMap::new(),
+                &HashMap::new(),
+                &VariableBindings::new(),
+            )?,
+            attach: self.attach,
         })
     }
 
-    /// collect the hay features referenced by each tag in the pattern.
+    /// bind the alpha-notation variables of the pattern (`[αvoi]`) to the hay's feature values.
+    /// returns None if a variable would take two different values, like in `[αvoi][-αvoi]`
+    /// matched against two voiced segments.
+    fn bind_variables(&self, hay: &PhonoString, alignment: &Alignment) -> Option<VariableBindings> {
+        let mut bindings = VariableBindings::new();
+        for (idx, (syl_info, _)) in self.pattern.tree.syls().iter().enumerate() {
+            let (hay_syl, _) = &hay.tree.layer_3()[alignment.syls[idx]];
+            if !hay_syl.bind_variables(&syl_info.features, &mut bindings) {
+                return None;
+            }
+        }
+        for (idx, (seg_info, _)) in self.pattern.tree.segs().iter().enumerate() {
+            let (hay_seg, _) = &hay.tree.layer_4()[alignment.segs[idx]];
+            if !hay_seg.bind_variables(&seg_info.features, &mut bindings) {
+                return None;
+            }
+        }
+        Some(bindings)
+    }
+
+    /// collect the hay syllables and segments referenced by each tag in the pattern, by index.
     /// returns None if two pattern nodes share a tag but map to different hay features
     /// (the twin-tag constraint: V_0...V_0 requires both vowels to be identical).
     fn build_captures(
         &self,
-        hay_segs: &[(SegmentFeatures, usize)],
-        hay_syls: &[(SyllableFeatures, usize)],
-        seg_offset: usize,
-        syl_offset: usize,
-    ) -> Option<(
-        HashMap<u32, SyllableFeatures>,
-        HashMap<u32, SegmentFeatures>,
-    )> {
-        let mut syl_captures: HashMap<u32, SyllableFeatures> = HashMap::new();
+        hay: &PhonoString,
+        alignment: &Alignment,
+    ) -> Option<(HashMap<u32, usize>, HashMap<u32, usize>)> {
+        let hay_syls = hay.tree.layer_3();
+        let mut syl_captures: HashMap<u32, usize> = HashMap::new();
         for (idx, (syl_info, _)) in self.pattern.tree.syls().iter().enumerate() {
             if let Some(id) = syl_info.tag {
-                let (hay_syl, _) = &hay_syls[syl_offset + idx];
+                let hay_syl = alignment.syls[idx];
                 if let Some(prev) = syl_captures.get(&id) {
-                    if prev != hay_syl {
+                    if hay_syls[*prev].0 != hay_syls[hay_syl].0 {
                         return None;
                     }
                 } else {
-                    syl_captures.insert(id, hay_syl.clone());
+                    syl_captures.insert(id, hay_syl);
                 }
             }
         }
 
-        let mut seg_captures: HashMap<u32, SegmentFeatures> = HashMap::new();
+        let hay_segs = hay.tree.layer_4();
+        let mut seg_captures: HashMap<u32, usize> = HashMap::new();
         for (idx, (seg_info, _)) in self.pattern.tree.segs().iter().enumerate() {
             if let Some(id) = seg_info.tag {
-                let (hay_seg, _) = &hay_segs[seg_offset + idx];
+                let hay_seg = alignment.segs[idx];
                 if let Some(prev) = seg_captures.get(&id) {
-                    if prev != hay_seg {
+                    if hay_segs[*prev].0 != hay_segs[hay_seg].0 {
                         return None;
                     }
                 } else {
-                    seg_captures.insert(id, hay_seg.clone());
+                    seg_captures.insert(id, hay_seg);
                 }
             }
         }
@@ -239,84 +450,127 @@ impl PhonoRule {
         Some((syl_captures, seg_captures))
     }
 
+    /// build the string replacing a match from the captures and variable bindings of the match.
+    /// A whole syllable of the replacement (see `TaggedPhonoString::is_whole_syllable`) tagged
+    /// like a whole syllable of the pattern gets the segments of the captured syllable.
+    /// returns None if the replacement tree uses a tag or variable that wasn't captured, which
+    /// doesn't happen for rules respecting `test_invariants`.
     fn build_replacement(
         &self,
-        syl_captures: &HashMap<u32, SyllableFeatures>,
-        seg_captures: &HashMap<u32, SegmentFeatures>,
-    ) -> PhonoString {
-        let mut tree = d3tree![];
-        for (_, syl_iter) in self.replace_tree.0.iter() {
+        hay: &PhonoString,
+        syl_captures: &HashMap<u32, usize>,
+        seg_captures: &HashMap<u32, usize>,
+        bindings: &VariableBindings,
+    ) -> Option<PhonoString> {
+        let replace = &self.replace_tree.0;
+        let mut tree = Depth5Tree::new();
+        for phrase_idx in 0..replace.len_0() {
             tree.push_depth_0(());
-            for (syl, seg_iter) in syl_iter {
-                let mut new_syl = SyllableFeatures::new_undef();
-                if let Some(id) = syl.tag {
-                    new_syl = new_syl
-                        + syl_captures
-                            .get(&id)
-                            .expect("Invalid rule: syllable capture id not found")
-                            .clone();
-                }
-                // captured features go left, explicit rule features go right so the
-                // rule's literal values take precedence over what was captured.
-                tree.push_depth_1(new_syl + syl.features.clone());
-                for seg in seg_iter {
-                    let mut new_seg = SegmentFeatures::new_undef();
-                    if let Some(id) = seg.tag {
-                        new_seg = new_seg
-                            + seg_captures
-                                .get(&id)
-                                .expect("Invalid rule: segment capture id not found")
-                                .clone();
+            for word_idx in replace.children_1(phrase_idx) {
+                tree.push_depth_1(());
+                for foot_idx in replace.children_2(word_idx) {
+                    tree.push_depth_2(replace.layer_2()[foot_idx].0.clone());
+                    for syl_idx in replace.children_3(foot_idx) {
+                        let syl = &replace.layer_3()[syl_idx].0;
+                        let captured = match syl.tag {
+                            Some(id) => Some(*syl_captures.get(&id)?),
+                            None => None,
+                        };
+
+                        let mut new_syl = SyllableFeatures::new_undef();
+                        if let Some(hay_syl) = captured {
+                            new_syl = new_syl + hay.tree.layer_3()[hay_syl].0.clone();
+                        }
+                        // captured features go left, explicit rule features go right so the
+                        // rule's literal values take precedence over what was captured.
+                        tree.push_depth_3(new_syl + syl.features.substitute_variables(bindings)?);
+
+                        if let Some(hay_syl) = captured
+                            && self.replace_tree.is_whole_syllable(syl_idx)
+                            && self.pattern.tree.is_whole_syllable_tag(syl.tag)
+                        {
+                            for (seg, _) in &hay.tree.layer_4()[hay.tree.children_4(hay_syl)] {
+                                tree.push_depth_4(seg.clone());
+                            }
+                        }
+                        for seg_idx in replace.children_4(syl_idx) {
+                            let seg = &replace.layer_4()[seg_idx].0;
+                            let mut new_seg = SegmentFeatures::new_undef();
+                            if let Some(id) = seg.tag {
+                                new_seg =
+                                    new_seg + hay.tree.layer_4()[*seg_captures.get(&id)?].0.clone();
+                            }
+                            tree.push_depth_4(
+                                new_seg + seg.features.substitute_variables(bindings)?,
+                            );
+                        }
                     }
-                    tree.push_depth_2(new_seg + seg.features.clone());
                 }
             }
         }
-        PhonoString { tree }
+        Some(PhonoString::from_tree(tree))
     }
 
-    /// returns false if tags in the pattern are not unique per level, or if the
-    /// replacement tree references a tag not present in the pattern.
+    /// returns false if tags in the pattern are not unique per level, if the
+    /// replacement tree references a tag not present in the pattern, or if the replacement tree
+    /// uses a variable not present in the pattern.
     pub fn test_invariants(&self) -> bool {
+        let pattern_vars = variables(&self.pattern.tree);
+        if !variables(&self.replace_tree).is_subset(&pattern_vars) {
+            return false;
+        }
+
         let mut syl_tags = HashSet::new();
         let mut seg_tags = HashSet::new();
 
-        for (_, syllables) in self.pattern.tree.0.iter() {
-            for (SyllableInfo { tag, features: _ }, segments) in syllables {
-                if let Some(tag) = tag
-                    && !syl_tags.insert(tag)
-                {
-                    return false; // duplicate syl tag in pattern
-                }
-                for SegmentInfo { tag, features: _ } in segments {
-                    if let Some(tag) = tag
-                        && !seg_tags.insert(tag)
-                    {
-                        return false; // duplicate seg tag in pattern
-                    }
-                }
+        for (SyllableInfo { tag, .. }, _) in self.pattern.tree.syls() {
+            if let Some(tag) = tag
+                && !syl_tags.insert(tag)
+            {
+                return false; // duplicate syl tag in pattern
+            }
+        }
+        for (SegmentInfo { tag, .. }, _) in self.pattern.tree.segs() {
+            if let Some(tag) = tag
+                && !seg_tags.insert(tag)
+            {
+                return false; // duplicate seg tag in pattern
             }
         }
 
         // remove each tag referenced by the replacement; if the tag was absent
         // (not in the pattern) remove returns false.
-        for (_, syllables) in self.replace_tree.0.iter() {
-            for (SyllableInfo { tag, features: _ }, segments) in syllables {
-                if let Some(tag) = tag
-                    && !syl_tags.remove(tag)
-                {
-                    return false; // replacement references unknown syl tag
-                }
-                for SegmentInfo { tag, features: _ } in segments {
-                    if let Some(tag) = tag
-                        && !seg_tags.remove(tag)
-                    {
-                        return false; // replacement references unknown seg tag
-                    }
-                }
+        for (SyllableInfo { tag, .. }, _) in self.replace_tree.syls() {
+            if let Some(tag) = tag
+                && !syl_tags.remove(tag)
+            {
+                return false; // replacement references unknown syl tag
+            }
+        }
+        for (SegmentInfo { tag, .. }, _) in self.replace_tree.segs() {
+            if let Some(tag) = tag
+                && !seg_tags.remove(tag)
+            {
+                return false; // replacement references unknown seg tag
             }
         }
 
         true
     }
 }
+
+/// alpha-notation variables used anywhere in the tagged string
+fn variables(string: &TaggedPhonoString) -> HashSet<u8> {
+    let syl_features = string
+        .syls()
+        .iter()
+        .flat_map(|(s, _)| s.features.features.iter());
+    let seg_features = string
+        .segs()
+        .iter()
+        .flat_map(|(s, _)| s.features.features().iter());
+    syl_features
+        .chain(seg_features)
+        .filter_map(|f| f.variable())
+        .collect()
+}
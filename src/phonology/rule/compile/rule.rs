@@ -1,59 +1,103 @@
 use crate::error::*;
-use crate::{
-    d3tree,
-    phonology::{
-        rule::{
-            ElementSequence, PhonoRule,
-            parse::{Element, RuleElements},
-        },
-        string::PhonoString,
+use crate::phonology::{
+    rule::{
+        ApplicationMode, ElementSequence, PhonoRule,
+        parse::{Element, RuleElements},
     },
+    string::{PhonoString, WeightOpts},
+    tree::Attach,
 };
 
-use super::tree::compile_tree;
+use super::tree::{compile_replacement_tree, compile_tree};
 
-pub fn compile_rule(rule_elements: RuleElements) -> PhonoRule {
-    let mut input_elems = rule_elements.pre_context_clone().elems;
-    input_elems.extend(rule_elements.input_clone().elems);
-    input_elems.extend(rule_elements.post_context_clone().elems);
+/// Compile a rule. The pattern is made of the pre-context, input and post-context. Matches only
+/// replace the input (the rule's focus) by the output, so the context is left untouched.
+pub fn compile_rule(rule_elements: RuleElements) -> Result<PhonoRule> {
+    let pre_context = rule_elements.pre_context_clone().elems;
+    let input = rule_elements.input_clone().elems;
+    let post_context = rule_elements.post_context_clone().elems;
 
-    let mut output_elems = rule_elements.pre_context_clone().elems;
-    output_elems.extend(rule_elements.output_clone().elems);
-    output_elems.extend(rule_elements.post_context_clone().elems);
+    let attach = insertion_side(&pre_context, &input);
+    let pre_context_len = pre_context.len();
+    let input_len = input.len();
+
+    let mut input_elems = pre_context;
+    input_elems.extend(input);
+    input_elems.extend(post_context);
+
+    let focus = count_units(&input_elems, pre_context_len)
+        ..count_units(&input_elems, pre_context_len + input_len);
+
+    let mut output_elems = rule_elements.output_clone().elems;
 
     let pattern = compile_tree(&input_elems, |old_tag, new_tag| {
         for output_elem in &mut output_elems {
-            if let Element::Features(output_syllable, _) = output_elem
-                && output_syllable.tag.is_some()
+            if let Some(output_syllable) = output_elem.syllable_mut()
                 && output_syllable.tag == Some(old_tag)
             {
                 output_syllable.tag = Some(new_tag);
             }
         }
-    })
-    .unwrap();
+    })?;
 
-    let replace_tree = compile_tree(&output_elems, |_, _| {}).unwrap().tree;
+    let replace_tree = compile_replacement_tree(&output_elems)?;
 
-    PhonoRule {
+    Ok(PhonoRule {
         pattern,
         replace_tree,
-    }
+        focus,
+        attach,
+        mode: ApplicationMode::default(),
+        weight: WeightOpts::default(),
+        source: None,
+    })
 }
 
-/// Compile elements without tags into a phonological string
-pub fn compile_untagged_elements(elements: ElementSequence) -> Result<PhonoString> {
-    let tree = compile_tree(&elements.elems, |_, _| {})?.tree;
-    let mut untagged = d3tree![];
-    for (_, syls) in tree.iter() {
-        untagged.push_depth_0(());
-        for (syl, segs) in syls {
-            untagged.push_depth_1(syl.features.clone());
-            for seg in segs {
-                untagged.push_depth_2(seg.features.clone());
+/// The number of units (see `TaggedPhonoString::unit_count`) in the first `n` elements of
+/// `elements`: the segments, and the syllable feature sets of syllables without segments, like
+/// `σ[+stress]` in `_.σ[+stress]`.
+fn count_units(elements: &[Element], n: usize) -> usize {
+    let mut units = 0;
+    let mut start = 0;
+    for syllable in elements.split(|e| e.boundary_level().is_some()) {
+        let has_segments = syllable
+            .iter()
+            .any(|e| matches!(e, Element::Features(_, _)));
+        let first_syllable_features = syllable
+            .iter()
+            .position(|e| matches!(e, Element::SyllableFeatures(_)));
+        for (idx, elem) in syllable.iter().enumerate() {
+            let is_unit = match elem {
+                Element::Features(_, _) => true,
+                Element::SyllableFeatures(_) => {
+                    !has_segments && first_syllable_features == Some(idx)
+                }
+                _ => false,
+            };
+            if is_unit && start + idx < n {
+                units += 1;
             }
         }
+        start += syllable.len() + 1;
     }
+    units
+}
 
-    Ok(PhonoString { tree: untagged })
+/// For insertions (empty input), the syllable the inserted segments go into.
+/// `∅ -> e / #_sC` inserts at the start of the following syllable. Otherwise, like in
+/// `∅ -> ə / C_#`, the segments go at the end of the preceding syllable. An input of boundaries
+/// only, like in `$ -> ∅ / V_V`, replaces the boundary between the syllables on both sides.
+fn insertion_side(pre_context: &[Element], input: &[Element]) -> Attach {
+    if !input.is_empty() && input.iter().all(|e| e.boundary_level().is_some()) {
+        return Attach::Both;
+    }
+    match pre_context.last().and_then(Element::boundary_level) {
+        Some(_) => Attach::Right,
+        None => Attach::Left,
+    }
+}
+
+/// Compile elements without tags into a phonological string
+pub fn compile_untagged_elements(elements: ElementSequence) -> Result<PhonoString> {
+    Ok(compile_tree(&elements.elems, |_, _| {})?.tree.untagged())
 }
@@ -1,92 +1,185 @@
 use crate::error::*;
-use crate::{
-    d3tree,
-    phonology::{
-        rule::{PatternBorder, PhonoStringPattern, SyllableInfo, parse::Element},
-        syllable::SyllableFeatures,
+use crate::phonology::{
+    feature::FeatureState,
+    foot::FootFeatures,
+    rule::{
+        BoundaryLevel, PatternBorder, PhonoStringPattern, SyllableInfo, TaggedPhonoString,
+        TaggedProsodicTree, parse::Element,
     },
+    string::weight_class_intersection,
+    syllable::{STRESS, SyllableFeatures},
+    tree::Depth5Tree,
 };
 
 /// Compiles a sequence of `elements` into a tagged phonological string.
 /// `syl_tag_squash_callback` is called when two syllable nodes are merged and a tag is lost.
 pub(super) fn compile_tree<F>(
     mut elements: &[Element],
-    mut syl_tag_squash_callback: F,
+    syl_tag_squash_callback: F,
 ) -> Result<PhonoStringPattern>
 where
     F: FnMut(u32, u32),
 {
     // parse possible initial boundary
-    let left_bound = match elements.first() {
-        Some(Element::Features(_, _)) => PatternBorder::Any,
-        Some(bound) => {
+    let left_bound = match elements.first().and_then(Element::boundary_level) {
+        Some(level) => {
             // remove initial boundary
             elements = &elements[1..];
-            match bound {
-                Element::WordBoundary => PatternBorder::Word,
-                Element::SyllableBoundary => PatternBorder::StrictSyllable,
-                _ => unreachable!(),
+            match level {
+                // a stress mark, like in `ˈta`, starts a syllable that may also start the word
+                BoundaryLevel::Syllable if starts_stressed(elements) => {
+                    PatternBorder::SyllableOrWord
+                }
+                level => level.into(),
             }
         }
-        None => PatternBorder::Any, // edge case: output deletion with no context
+        None => PatternBorder::Any, // also an output deletion with no context
     };
 
     // parse possible final boundary
-    let right_bound = match elements.last() {
-        Some(Element::Features(_, _)) => PatternBorder::Any,
-        Some(bound) => {
+    let right_bound = match elements.last().and_then(Element::boundary_level) {
+        Some(level) => {
             // remove final boundary
             elements = &elements[..(elements.len() - 1)];
-            match bound {
-                Element::WordBoundary => PatternBorder::Word,
-                Element::SyllableBoundary => PatternBorder::StrictSyllable,
-                _ => unreachable!(),
-            }
+            level.into()
         }
-        None => PatternBorder::Any, // edge case: output deletion with no context
+        None => PatternBorder::Any, // also an output deletion with no context
     };
 
-    // parse elements that make up the tree
-    let first_syllable = SyllableInfo::new(None, SyllableFeatures::new_undef());
-    // in case there are no elements in the tree, populate it with a placeholder word and syllable
-    let mut tree = d3tree![() => [first_syllable => []]];
-    let mut is_new_syllable = false;
-    let mut is_new_word = false;
+    let (tree, _) = build_tree(elements, syl_tag_squash_callback);
+
+    Ok(PhonoStringPattern::from_tree(tree, left_bound, right_bound))
+}
+
+/// true if the first element is a segment of a stressed syllable
+fn starts_stressed(elements: &[Element]) -> bool {
+    matches!(
+        elements.first(),
+        Some(Element::Features(syllable, _))
+            if syllable.features.features[STRESS as usize] == FeatureState::POS
+    )
+}
+
+/// Compiles the output `elements` of a rule into the tree that replaces a match.
+/// Unlike `compile_tree`, boundaries at the edges are kept: a leading boundary leaves an empty
+/// first syllable (or foot, word, phrase), and a trailing boundary adds an empty last syllable (or
+/// foot, word, phrase), so the boundary lands between the replacement and the rest of the string.
+pub(super) fn compile_replacement_tree(elements: &[Element]) -> Result<TaggedPhonoString> {
+    let (mut tree, pending) = build_tree(elements, |_, _| {});
+    if let Some(level) = pending {
+        push_empty_nodes(&mut tree, level, FootFeatures::new_undef());
+    }
+
+    Ok(TaggedPhonoString::from_tree(tree))
+}
+
+/// Builds the tree of a sequence of elements. Returns the tree, and the level of the last
+/// boundary if no segment came after it.
+fn build_tree<F>(
+    elements: &[Element],
+    mut syl_tag_squash_callback: F,
+) -> (TaggedProsodicTree, Option<BoundaryLevel>)
+where
+    F: FnMut(u32, u32),
+{
+    // in case there are no elements in the tree, populate it with a placeholder phrase, word,
+    // foot and syllable
+    let mut tree = Depth5Tree::new();
+    tree.push_depth_0(());
+    push_empty_nodes(&mut tree, BoundaryLevel::Word, FootFeatures::new_undef());
+    // the highest boundary since the last segment
+    let mut pending: Option<BoundaryLevel> = None;
+    // the features of the next foot, if a foot boundary is pending
+    let mut next_foot = FootFeatures::new_undef();
     for element in elements {
         match element {
             Element::Features(syllable, segment) => {
-                if is_new_word {
-                    tree.push_depth_0(());
-                    is_new_word = false;
-                }
-
-                if is_new_syllable {
-                    tree.push_depth_1(syllable.clone());
-                    is_new_syllable = false;
-                } else {
-                    // merge syllable with last existing syllable
-                    let last_syl = tree.get_depth_1_mut(tree.len_1() - 1);
-                    // existing last syllable takes precedence, goes on rhs of addition
-                    last_syl.features = syllable.features.clone() + last_syl.features.clone();
-
-                    if let Some(old_tag) = syllable.tag {
-                        if let Some(new_tag) = last_syl.tag {
-                            syl_tag_squash_callback(old_tag, new_tag);
-                        } else {
-                            last_syl.tag = Some(old_tag)
-                        }
+                match pending.take() {
+                    Some(level) => {
+                        push_empty_nodes(&mut tree, level, next_foot);
+                        next_foot = FootFeatures::new_undef();
+                        let last_syl = tree.get_depth_3_mut(tree.len_3() - 1);
+                        *last_syl = syllable.clone();
+                    }
+                    None => {
+                        let last_syl = tree.get_depth_3_mut(tree.len_3() - 1);
+                        merge_syllable(last_syl, syllable, &mut syl_tag_squash_callback);
                     }
                 }
 
-                tree.push_depth_2(segment.clone());
+                tree.push_depth_4(segment.clone());
             }
-            Element::WordBoundary => {
-                is_new_word = true;
-                is_new_syllable = true;
+            // like the syllable of a segment, without the segment
+            Element::SyllableFeatures(syllable) => match pending.take() {
+                Some(level) => {
+                    push_empty_nodes(&mut tree, level, next_foot);
+                    next_foot = FootFeatures::new_undef();
+                    let last_syl = tree.get_depth_3_mut(tree.len_3() - 1);
+                    *last_syl = syllable.clone();
+                }
+                None => {
+                    let last_syl = tree.get_depth_3_mut(tree.len_3() - 1);
+                    merge_syllable(last_syl, syllable, &mut syl_tag_squash_callback);
+                }
+            },
+            Element::FootFeatures(features) => {
+                if pending.is_some_and(|level| level >= BoundaryLevel::Foot) {
+                    next_foot = next_foot + features.clone();
+                } else {
+                    let last_foot = tree.get_depth_2_mut(tree.len_2() - 1);
+                    *last_foot = last_foot.clone() + features.clone();
+                }
             }
-            Element::SyllableBoundary => is_new_syllable = true,
+            boundary => pending = pending.max(boundary.boundary_level()),
         }
     }
 
-    Ok(PhonoStringPattern::new(tree, left_bound, right_bound))
+    (tree, pending)
+}
+
+/// Merge `syllable` into the existing syllable `last_syl`
+fn merge_syllable<F>(
+    last_syl: &mut SyllableInfo,
+    syllable: &SyllableInfo,
+    syl_tag_squash_callback: &mut F,
+) where
+    F: FnMut(u32, u32),
+{
+    // existing last syllable takes precedence, goes on rhs of addition
+    last_syl.features = syllable.features.clone() + last_syl.features.clone();
+    last_syl.explicit |= syllable.explicit;
+    // both weight classes apply
+    last_syl.weight = match (last_syl.weight.take(), syllable.weight.clone()) {
+        (Some(a), Some(b)) => Some(weight_class_intersection(a, b)),
+        (a, b) => a.or(b),
+    };
+
+    if let Some(old_tag) = syllable.tag {
+        if let Some(new_tag) = last_syl.tag {
+            syl_tag_squash_callback(old_tag, new_tag);
+        } else {
+            last_syl.tag = Some(old_tag)
+        }
+    }
+}
+
+/// Push the empty nodes that start a new phrase, word, foot or syllable after a boundary of
+/// `level`. A new foot gets `foot_features`.
+fn push_empty_nodes(
+    tree: &mut TaggedProsodicTree,
+    level: BoundaryLevel,
+    foot_features: FootFeatures,
+) {
+    if level >= BoundaryLevel::Phrase {
+        tree.push_depth_0(());
+    }
+    if level >= BoundaryLevel::Word {
+        tree.push_depth_1(());
+    }
+    if level >= BoundaryLevel::Foot {
+        tree.push_depth_2(foot_features);
+    }
+    if level >= BoundaryLevel::Syllable {
+        tree.push_depth_3(SyllableInfo::new(None, SyllableFeatures::new_undef()));
+    }
 }
@@ -0,0 +1,94 @@
+use std::fmt;
+
+use crate::phonology::{
+    rule::{PatternMatch, RuleSource},
+    segment::Notation,
+    string::PhonoString,
+};
+
+/// The intermediate strings of a rule set applied to a string
+#[derive(Debug, Clone)]
+pub struct Derivation {
+    pub input: PhonoString,
+    /// one step per rule and stress step of the set, in order, and a last step resyllabifying
+    /// the output with `Resyllabify::AtEnd`
+    pub steps: Vec<DerivationStep>,
+}
+
+/// The application of one rule (or stress step) in a derivation
+#[derive(Debug, Clone)]
+pub struct DerivationStep {
+    /// index of the rule in the rule set. Stress steps and the resyllabification have the index
+    /// of the rule after them.
+    pub rule_idx: usize,
+    pub source: Option<RuleSource>,
+    /// true for the resyllabification after the last rule (see `Resyllabify::AtEnd`), which has
+    /// a match per new syllable
+    pub resyllabification: bool,
+    /// the applied matches, in application order. Each range is relative to the string the match
+    /// was applied to.
+    pub matches: Vec<PatternMatch>,
+    pub output: PhonoString,
+}
+
+impl Derivation {
+    /// The final string
+    pub fn output(&self) -> &PhonoString {
+        self.steps.last().map_or(&self.input, |step| &step.output)
+    }
+
+    /// Format the derivation as a table, with a row per rule that applied
+    pub fn format_table(&self) -> String {
+        self.format_table_in(Notation::Ipa)
+    }
+
+    /// like `format_table`, with the strings in `notation`
+    pub fn format_table_in(&self, notation: Notation) -> String {
+        let mut rows = vec![(
+            "".to_string(),
+            "".to_string(),
+            self.input.format_in(notation),
+        )];
+        for step in self.steps.iter().filter(|step| !step.matches.is_empty()) {
+            let rule = match &step.source {
+                _ if step.resyllabification => ("".to_string(), "resyllabify".to_string()),
+                Some(source) => (source.line.to_string(), source.text.clone()),
+                None => (format!("#{}", step.rule_idx + 1), "".to_string()),
+            };
+            let changes = step
+                .matches
+                .iter()
+                .map(|m| {
+                    let replace_with = m.replace_with.format_in(notation);
+                    format!("{}..{} → [{replace_with}]", m.range.start, m.range.end)
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            rows.push((
+                rule.0,
+                rule.1,
+                format!("{}    {changes}", step.output.format_in(notation)),
+            ));
+        }
+
+        let width_0 = rows.iter().map(|r| r.0.chars().count()).max().unwrap_or(0);
+        let width_1 = rows.iter().map(|r| r.1.chars().count()).max().unwrap_or(0);
+        let mut table = String::new();
+        for (line, rule, output) in rows {
+            let line_pad = width_0 - line.chars().count();
+            let rule_pad = width_1 - rule.chars().count();
+            table.push_str(&format!(
+                "{}{line} | {rule}{} | {output}\n",
+                " ".repeat(line_pad),
+                " ".repeat(rule_pad)
+            ));
+        }
+        table
+    }
+}
+
+impl fmt::Display for Derivation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.format_table())
+    }
+}
@@ -1,11 +1,15 @@
 mod base;
 mod compile;
+mod derivation;
+mod mode;
 mod parse;
 mod pattern;
 mod set;
 
 pub use base::*;
 pub use compile::*;
+pub use derivation::*;
+pub use mode::*;
 pub use parse::*;
 pub use pattern::*;
 pub use set::*;
@@ -0,0 +1,198 @@
+use crate::error::*;
+use crate::phonology::{
+    rule::{PatternMatch, PhonoRule},
+    string::{EmptySyllablePolicy, PhonoString},
+};
+
+/// Maximum number of passes of an iterative rule
+pub const MAX_ITERATIONS: usize = 100;
+
+/// Maximum number of segments in the output of an iterative rule, as a multiple of the number
+/// of segments of its input
+pub const MAX_ITERATIVE_GROWTH: usize = 16;
+
+/// How the matches of a rule are applied to a string.
+/// In rules, a mode is selected with a prefix, like `ltr: t -> d / V_V`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ApplicationMode {
+    /// `sim:` all matches are found on the input string, and applied at once. A match that
+    /// overlaps an earlier match is skipped.
+    #[default]
+    Simultaneous,
+    /// `ltr:` apply the leftmost match, then match again after it on the changed string
+    LeftToRight,
+    /// `rtl:` apply the rightmost match, then match again before it on the changed string
+    RightToLeft,
+    /// `iter:` apply simultaneously until the string doesn't change, or comes back to a previous
+    /// state. Fails if that doesn't happen within `MAX_ITERATIONS` passes, or if the string
+    /// grows past `MAX_ITERATIVE_GROWTH` times its length.
+    Iterative,
+}
+
+impl ApplicationMode {
+    /// the prefix selecting this mode in rules
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            ApplicationMode::Simultaneous => "sim",
+            ApplicationMode::LeftToRight => "ltr",
+            ApplicationMode::RightToLeft => "rtl",
+            ApplicationMode::Iterative => "iter",
+        }
+    }
+}
+
+impl PhonoRule {
+    /// Apply the rule to `string` according to its mode. Syllables and words emptied by the rule
+    /// are removed, according to `empty_syllables`.
+    pub fn apply(
+        &self,
+        string: PhonoString,
+        empty_syllables: EmptySyllablePolicy,
+    ) -> Result<PhonoString> {
+        self.apply_traced(string, empty_syllables, &mut vec![])
+    }
+
+    /// Like `apply`, also pushes the applied matches to `applied`, in application order. The
+    /// range of a match is relative to the string it was applied to.
+    pub fn apply_traced(
+        &self,
+        string: PhonoString,
+        empty_syllables: EmptySyllablePolicy,
+        applied: &mut Vec<PatternMatch>,
+    ) -> Result<PhonoString> {
+        match self.mode {
+            ApplicationMode::Simultaneous => {
+                self.apply_simultaneous(string, empty_syllables, applied)
+            }
+            ApplicationMode::LeftToRight => {
+                self.apply_left_to_right(string, empty_syllables, applied)
+            }
+            ApplicationMode::RightToLeft => {
+                self.apply_right_to_left(string, empty_syllables, applied)
+            }
+            ApplicationMode::Iterative => self.apply_iterative(string, empty_syllables, applied),
+        }
+    }
+
+    fn apply_simultaneous(
+        &self,
+        mut string: PhonoString,
+        empty_syllables: EmptySyllablePolicy,
+        applied: &mut Vec<PatternMatch>,
+    ) -> Result<PhonoString> {
+        let mut matches = self.find(string.clone());
+        matches.sort_by_key(|m| (m.range.start, m.range.end));
+
+        let mut kept: Vec<PatternMatch> = vec![];
+        for m in matches {
+            if kept.last().is_none_or(|last| is_after(&m, last.range.end)) {
+                kept.push(m);
+            }
+        }
+
+        // right to left, so the ranges of the remaining matches stay valid
+        for m in kept.into_iter().rev() {
+            applied.push(m.clone());
+            string = m.apply(string)?;
+        }
+        Ok(string.prune_empty(empty_syllables))
+    }
+
+    fn apply_left_to_right(
+        &self,
+        mut string: PhonoString,
+        empty_syllables: EmptySyllablePolicy,
+        applied: &mut Vec<PatternMatch>,
+    ) -> Result<PhonoString> {
+        // end of the last change
+        let mut cursor = None;
+        loop {
+            let next = self
+                .find(string.clone())
+                .into_iter()
+                .filter(|m| cursor.is_none_or(|cursor| is_after(m, cursor)))
+                .min_by_key(|m| (m.range.start, m.range.end));
+            let Some(m) = next else {
+                return Ok(string);
+            };
+
+            cursor = Some(m.range.start + m.replace_with.tree.len_4());
+            applied.push(m.clone());
+            string = m.apply(string)?.prune_empty(empty_syllables);
+        }
+    }
+
+    fn apply_right_to_left(
+        &self,
+        mut string: PhonoString,
+        empty_syllables: EmptySyllablePolicy,
+        applied: &mut Vec<PatternMatch>,
+    ) -> Result<PhonoString> {
+        // start of the last change
+        let mut cursor = None;
+        loop {
+            let next = self
+                .find(string.clone())
+                .into_iter()
+                .filter(|m| cursor.is_none_or(|cursor| is_before(m, cursor)))
+                .max_by_key(|m| (m.range.end, m.range.start));
+            let Some(m) = next else {
+                return Ok(string);
+            };
+
+            cursor = Some(m.range.start);
+            applied.push(m.clone());
+            string = m.apply(string)?.prune_empty(empty_syllables);
+        }
+    }
+
+    fn apply_iterative(
+        &self,
+        mut string: PhonoString,
+        empty_syllables: EmptySyllablePolicy,
+        applied: &mut Vec<PatternMatch>,
+    ) -> Result<PhonoString> {
+        let max_len = string.tree.len_4().max(1) * MAX_ITERATIVE_GROWTH;
+        let mut previous = vec![];
+        for _ in 0..MAX_ITERATIONS {
+            let mut pass_applied = vec![];
+            let next =
+                self.apply_simultaneous(string.clone(), empty_syllables, &mut pass_applied)?;
+            if next == string || previous.contains(&next) {
+                // fixpoint, or cycle
+                return Ok(string);
+            }
+            if next.tree.len_4() > max_len {
+                return Err(Error::other(format!(
+                    "iterative rule{} grows the string past {max_len} segments",
+                    self.source_suffix()
+                )));
+            }
+            applied.extend(pass_applied);
+            previous.push(std::mem::replace(&mut string, next));
+        }
+        Err(Error::other(format!(
+            "iterative rule{} still changes the string after {MAX_ITERATIONS} passes",
+            self.source_suffix()
+        )))
+    }
+
+    // the line of the rule, to name it in errors
+    fn source_suffix(&self) -> String {
+        self.source
+            .as_ref()
+            .map(|source| format!(" \"{}\" (line {})", source.text, source.line))
+            .unwrap_or_default()
+    }
+}
+
+/// true if `m` starts after `position`, without touching it. An insertion at `position` would
+/// be adjacent to the change ending there, so it doesn't count.
+fn is_after(m: &PatternMatch, position: usize) -> bool {
+    m.range.start > position || (m.range.start == position && !m.range.is_empty())
+}
+
+/// true if `m` ends before `position`, without touching it
+fn is_before(m: &PatternMatch, position: usize) -> bool {
+    m.range.end < position || (m.range.end == position && !m.range.is_empty())
+}
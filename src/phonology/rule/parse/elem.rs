@@ -1,19 +1,61 @@
 use std::iter::zip;
 
 use crate::error::*;
+use crate::phonology::feature::Variable;
+use crate::phonology::foot::FootFeatures;
 use crate::phonology::rule::{
-    SegmentInfo, SyllableInfo,
-    parse::{parse_elem::parse_rule_elems, pattern::RuleStrings},
+    BoundaryLevel, SegmentInfo, SyllableInfo,
+    parse::{
+        parse_elem::parse_rule_elems_with_text,
+        pattern::{RuleStrings, SourcedText},
+    },
 };
+use crate::phonology::segment::{NaturalClasses, Notation};
 
-/// a boundary or a feature set for a segment
+/// a boundary, a feature set for a segment, or a feature set for a syllable or a foot
 #[derive(Debug, Clone, PartialEq)]
 pub enum Element {
     Features(SyllableInfo, SegmentInfo),
+    /// features of the syllable of the following segments, like `σ[+heavy]`
+    SyllableFeatures(SyllableInfo),
+    /// features of the foot of the following segments, like `Σ[+trochaic]`
+    FootFeatures(FootFeatures),
+    PhraseBoundary,
     WordBoundary,
+    FootBoundary,
     SyllableBoundary,
 }
 
+impl Element {
+    /// the level of a boundary element, None for feature sets
+    pub fn boundary_level(&self) -> Option<BoundaryLevel> {
+        match self {
+            Element::Features(_, _) | Element::SyllableFeatures(_) | Element::FootFeatures(_) => {
+                None
+            }
+            Element::PhraseBoundary => Some(BoundaryLevel::Phrase),
+            Element::WordBoundary => Some(BoundaryLevel::Word),
+            Element::FootBoundary => Some(BoundaryLevel::Foot),
+            Element::SyllableBoundary => Some(BoundaryLevel::Syllable),
+        }
+    }
+
+    /// the syllable of a segment or of a syllable feature set
+    pub fn syllable(&self) -> Option<&SyllableInfo> {
+        match self {
+            Element::Features(syl, _) | Element::SyllableFeatures(syl) => Some(syl),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn syllable_mut(&mut self) -> Option<&mut SyllableInfo> {
+        match self {
+            Element::Features(syl, _) | Element::SyllableFeatures(syl) => Some(syl),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ElementSequence {
     pub elems: Vec<Element>,
@@ -48,19 +90,64 @@ impl RuleElements {
             post_context,
         };
 
+        let has_position =
+            |e: &Element| matches!(e, Element::Features(_, seg) if seg.position.is_some());
+        if rule.output.elems.iter().any(has_position) {
+            return Err(Error::rule_syntax(
+                "syllable positions, like [+coda], only go in the input and context",
+                None,
+            ));
+        }
+        let has_weight =
+            |e: &Element| matches!(e, Element::SyllableFeatures(syl) if syl.weight.is_some());
+        if rule.output.elems.iter().any(has_weight) {
+            return Err(Error::rule_syntax(
+                "syllable weights, like σ[+heavy], only go in the input and context",
+                None,
+            ));
+        }
+
+        rule.lower_deleted_boundary();
+
         if !rule.check_invariants() {
-            return Err(Error::other("RuleElements invariants not respected"));
+            return Err(Error::tag(
+                "tags and variables of the output must be in the input or context",
+                None,
+            ));
         }
 
         // Tag inputs and outputs
         if !rule.tag_all() {
             // tagging failed
-            return Err(Error::other("RuleElements : couldn't tag all !"));
+            return Err(Error::tag(
+                "couldn't pair the input and output elements",
+                None,
+            ));
         }
 
         Ok(rule)
     }
 
+    /// An input of boundaries only, like in `$ -> ∅ / V_V`, changes the boundary between two
+    /// segments (see `Attach::Both`). Deleting a boundary leaves the boundary below it: `# -> ∅`
+    /// joins two words, which keep their syllables, and `$ -> ∅` joins two syllables.
+    fn lower_deleted_boundary(&mut self) {
+        let levels = self.input.elems.iter().map(Element::boundary_level);
+        let Some(Some(level)) = levels.clone().max() else {
+            return;
+        };
+        if levels.clone().any(|level| level.is_none()) || !self.output.elems.is_empty() {
+            return;
+        }
+
+        let lower = match level {
+            BoundaryLevel::Phrase => Some(Element::WordBoundary),
+            BoundaryLevel::Word | BoundaryLevel::Foot => Some(Element::SyllableBoundary),
+            BoundaryLevel::Syllable | BoundaryLevel::Segment => None,
+        };
+        self.output.elems.extend(lower);
+    }
+
     fn check_invariants(&self) -> bool {
         let input_and_ctx_syl_tags: Vec<u32> = self
             .input
@@ -68,13 +155,7 @@ impl RuleElements {
             .iter()
             .chain(self.pre_context.elems.iter())
             .chain(self.post_context.elems.iter())
-            .filter_map(|e| {
-                if let Element::Features(syl, _) = e {
-                    syl.tag
-                } else {
-                    None
-                }
-            })
+            .filter_map(|e| e.syllable()?.tag)
             .collect();
 
         let input_and_ctx_seg_tags: Vec<u32> = self
@@ -92,39 +173,74 @@ impl RuleElements {
             })
             .collect();
 
+        let input_and_ctx_vars: Vec<Variable> = self
+            .input
+            .elems
+            .iter()
+            .chain(self.pre_context.elems.iter())
+            .chain(self.post_context.elems.iter())
+            .flat_map(element_variables)
+            .collect();
+
         for elem in &self.output.elems {
-            if let Element::Features(syl, seg) = elem {
-                if let Some(id) = syl.tag
-                    && !input_and_ctx_syl_tags.contains(&id)
-                {
-                    return false;
-                }
-                if let Some(id) = seg.tag
-                    && !input_and_ctx_seg_tags.contains(&id)
-                {
-                    return false;
-                }
+            if !element_variables(elem).all(|v| input_and_ctx_vars.contains(&v)) {
+                return false;
+            }
+            if let Some(id) = elem.syllable().and_then(|syl| syl.tag)
+                && !input_and_ctx_syl_tags.contains(&id)
+            {
+                return false;
+            }
+            if let Element::Features(_, seg) = elem
+                && let Some(id) = seg.tag
+                && !input_and_ctx_seg_tags.contains(&id)
+            {
+                return false;
             }
         }
 
         true
     }
 
-    /// Apply the element parsing algo to each possible input, output and context.
-    pub fn from_strings(strings: RuleStrings) -> Result<Vec<Self>> {
-        // manage the parsing error and remainder
-        fn parse(input: String) -> Result<ElementSequence> {
-            if input.is_empty() {
-                return Ok(ElementSequence::new(vec![]));
+    /// Apply the element parsing algo to each possible input, output and context, with the
+    /// natural classes of `classes`. `source` is the text the rule was parsed from, which error
+    /// spans refer to.
+    pub fn from_strings(
+        strings: RuleStrings,
+        source: &str,
+        classes: &NaturalClasses,
+    ) -> Result<Vec<Self>> {
+        // manage the parsing error and remainder. returns the elements, with their spans
+        let parse = |input: SourcedText| -> Result<Vec<(Element, Option<Span>)>> {
+            if input.as_str().is_empty() {
+                return Ok(vec![]);
             }
-            let (rem, elems) = parse_rule_elems(&input).map_err(Error::other)?;
+            let span_of = |part: &str| input.source_of(part).map(|part| Span::of(source, part));
+            let text = input.as_str();
+            let (rem, elems) =
+                parse_rule_elems_with_text(text, Notation::Ipa, classes).map_err(|e| {
+                    let part = match &e {
+                        nom::Err::Error(e) | nom::Err::Failure(e) => e.input,
+                        nom::Err::Incomplete(_) => text,
+                    };
+                    Error::segment(format!("couldn't parse \"{text}\""), span_of(part))
+                })?;
             if !rem.is_empty() {
-                return Err(Error::other(format!(
-                    "Cound't completely parse element sequence, remainder=\"{rem}\""
-                )));
+                return Err(Error::segment(
+                    format!("couldn't parse \"{rem}\" in \"{text}\""),
+                    span_of(rem),
+                ));
             }
+            let elems: Vec<(Element, Option<Span>)> = elems
+                .into_iter()
+                .map(|(elem, text)| (elem, span_of(text)))
+                .collect();
+            check_weights(&elems)?;
             Ok(elems)
-        }
+        };
+        let without_spans = |elems: Vec<(Element, Option<Span>)>| {
+            ElementSequence::new(elems.into_iter().map(|(elem, _)| elem).collect())
+        };
 
         let mut inputs = vec![];
         let mut outputs = vec![];
@@ -133,7 +249,7 @@ impl RuleElements {
         for input_opts in strings.input {
             let mut parsed_input_opts = vec![];
             for opt in input_opts {
-                parsed_input_opts.push(parse(opt)?);
+                parsed_input_opts.push(without_spans(parse(opt)?));
             }
             inputs.push(parsed_input_opts);
         }
@@ -141,14 +257,18 @@ impl RuleElements {
             outputs.push(parse(output)?);
         }
         for pre_context_opt in strings.pre_context {
-            pre_context.push(parse(pre_context_opt)?);
+            pre_context.push(without_spans(parse(pre_context_opt)?));
         }
         for post_context_opt in strings.post_context {
-            post_context.push(parse(post_context_opt)?);
+            post_context.push(without_spans(parse(post_context_opt)?));
         }
 
         let mut rules = vec![];
         for (input, output) in zip(inputs, outputs) {
+            for input_opt in &input {
+                check_inserted_segments(input_opt, &output)?;
+            }
+            let output = without_spans(output);
             for input_opt in &input {
                 for pre_context_opt in &pre_context {
                     for post_context_opt in &post_context {
@@ -229,29 +349,87 @@ impl RuleElements {
             .chain(self.pre_context.elems.iter())
             .chain(self.post_context.elems.iter())
         {
-            if let Element::Features(syl, seg) = elem {
-                if let Some(id) = syl.tag {
-                    syl_tags.push(id);
-                }
-                if let Some(id) = seg.tag {
-                    seg_tags.push(id);
-                }
+            if let Some(id) = elem.syllable().and_then(|syl| syl.tag) {
+                syl_tags.push(id);
+            }
+            if let Element::Features(_, seg) = elem
+                && let Some(id) = seg.tag
+            {
+                seg_tags.push(id);
             }
         }
     }
 }
 
+/// An insertion, like `∅ -> ə / C_C`, has no input segment to give the features its output
+/// segments leave undefined, so they need all of them, unless a tag copies them from the context.
+/// `output` has the spans of the elements.
+fn check_inserted_segments(
+    input: &ElementSequence,
+    output: &[(Element, Option<Span>)],
+) -> Result<()> {
+    if input
+        .elems
+        .iter()
+        .any(|e| matches!(e, Element::Features(_, _)))
+    {
+        return Ok(());
+    }
+    let incomplete = output.iter().find(|(elem, _)| match elem {
+        Element::Features(_, seg) => seg.tag.is_none() && !seg.features.is_complete(),
+        _ => false,
+    });
+    match incomplete {
+        Some((_, span)) => Err(Error::rule_syntax(
+            "an inserted segment needs all its features, like ə rather than [+syl]",
+            span.clone(),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Weight classes narrow each other, so contradictory ones, like in `σ[+heavy-heavy]`, would
+/// never match. `elems` has the spans of the elements.
+fn check_weights(elems: &[(Element, Option<Span>)]) -> Result<()> {
+    let contradictory = elems.iter().find(|(elem, _)| match elem {
+        Element::SyllableFeatures(syl) => syl.weight.as_ref().is_some_and(|w| w.is_empty()),
+        _ => false,
+    });
+    match contradictory {
+        Some((_, span)) => Err(Error::rule_syntax(
+            "contradictory syllable weights, no syllable matches them",
+            span.clone(),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// alpha-notation variables used by the element
+fn element_variables(elem: &Element) -> impl Iterator<Item = Variable> + '_ {
+    let syl_features = elem.syllable().map(|syl| syl.features.features.iter());
+    let seg_features = match elem {
+        Element::Features(_, seg) => Some(seg.features.features().iter()),
+        _ => None,
+    };
+    let features = syl_features.into_iter().flatten();
+    features
+        .chain(seg_features.into_iter().flatten())
+        .filter_map(|f| f.variable())
+}
+
 fn next_tag(existing: &mut Vec<u32>) -> u32 {
     let tag = (0u32..).find(|t| !existing.contains(t)).unwrap();
     existing.push(tag);
     tag
 }
 
+/// a syllable written with `σ` is always paired, since a whole syllable in the output keeps the
+/// segments of its pair
 fn needs_syl_tag(elem: &Element) -> bool {
-    if let Element::Features(syl, _) = elem {
-        syl.tag.is_none() && !syl.features.is_complete()
-    } else {
-        false
+    match elem {
+        Element::Features(syl, _) => syl.tag.is_none() && !syl.features.is_complete(),
+        Element::SyllableFeatures(syl) => syl.tag.is_none(),
+        _ => false,
     }
 }
 
@@ -278,11 +456,10 @@ fn tag_paired_syl(input: &mut [Element], output: &mut [Element], existing: &mut
         }
 
         let tag = next_tag(existing);
-        if let Element::Features(syl, _) = &mut input[ii] {
-            syl.tag = Some(tag);
-        }
-        if let Element::Features(syl, _) = &mut output[oi] {
-            syl.tag = Some(tag);
+        for elem in [&mut input[ii], &mut output[oi]] {
+            if let Some(syl) = elem.syllable_mut() {
+                syl.tag = Some(tag);
+            }
         }
         ii += 1;
         oi += 1;
@@ -290,6 +467,25 @@ fn tag_paired_syl(input: &mut [Element], output: &mut [Element], existing: &mut
 }
 
 fn tag_paired_seg(input: &mut [Element], output: &mut [Element], existing: &mut Vec<u32>) {
+    let is_seg = |e: &&mut Element| matches!(e, Element::Features(_, _));
+    if input.iter_mut().filter(is_seg).count() == output.iter_mut().filter(is_seg).count() {
+        // segments pair up one to one: an incomplete output segment (like `[+voi]`) is a change
+        // on the input segment at the same position, even if that input segment is complete.
+        let input_segs = input.iter_mut().filter(is_seg);
+        let output_segs = output.iter_mut().filter(is_seg);
+        for (input_elem, output_elem) in zip(input_segs, output_segs) {
+            if let (Element::Features(_, in_seg), Element::Features(_, out_seg)) =
+                (input_elem, output_elem)
+                && out_seg.tag.is_none()
+                && !out_seg.features.is_complete()
+            {
+                let tag = *in_seg.tag.get_or_insert_with(|| next_tag(existing));
+                out_seg.tag = Some(tag);
+            }
+        }
+        return;
+    }
+
     let mut ii = 0;
     let mut oi = 0;
 
@@ -317,13 +513,17 @@ fn tag_paired_seg(input: &mut [Element], output: &mut [Element], existing: &mut
 
 fn tag_context(elems: &mut [Element], syl_tags: &mut Vec<u32>, seg_tags: &mut Vec<u32>) {
     for elem in elems {
-        if let Element::Features(syl, seg) = elem {
-            if syl.tag.is_none() && !syl.features.is_complete() {
-                syl.tag = Some(next_tag(syl_tags));
-            }
-            if seg.tag.is_none() && !seg.features.is_complete() {
-                seg.tag = Some(next_tag(seg_tags));
-            }
+        if let Some(syl) = elem.syllable_mut()
+            && syl.tag.is_none()
+            && !syl.features.is_complete()
+        {
+            syl.tag = Some(next_tag(syl_tags));
+        }
+        if let Element::Features(_, seg) = elem
+            && seg.tag.is_none()
+            && !seg.features.is_complete()
+        {
+            seg.tag = Some(next_tag(seg_tags));
         }
     }
 }
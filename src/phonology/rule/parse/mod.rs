@@ -6,8 +6,8 @@ mod parse_patterns;
 mod pattern;
 pub(crate) use elem::{Element, ElementSequence, RuleElements};
 pub use opts::*;
-pub(crate) use parse_elem::parse_rule_elems;
-pub(crate) use parse_patterns::parse_rule_patterns;
+pub(crate) use parse_elem::parse_rule_elems_in;
+pub(crate) use parse_patterns::{parse_application_mode, parse_rule_patterns};
 
 #[cfg(test)]
 mod test;
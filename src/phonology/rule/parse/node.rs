@@ -1,16 +1,21 @@
+use std::borrow::Cow;
+
+use crate::phonology::rule::parse::pattern::SourcedText;
+
 /// Phonological Rule Parse Node
 /// Nodes that make the rule parse tree
 #[derive(Debug, Clone, PartialEq)]
 pub enum Node<'a> {
     Sequence(Vec<Node<'a>>),
-    Leaf(&'a str),
+    /// borrowed from the rule, or owned when it comes from a natural class
+    Leaf(Cow<'a, str>),
     Branch(Vec<Node<'a>>),
     Null,
 }
 
-impl Node<'_> {
+impl<'a> Node<'a> {
     /// lists all possible element sequences, taking each branch combination.
-    pub fn collect_element_sequences(&self, paths: &mut Vec<String>) {
+    pub fn collect_element_sequences(&self, paths: &mut Vec<SourcedText<'a>>) {
         match self {
             Node::Sequence(nodes) => {
                 for child in nodes {
@@ -18,14 +23,18 @@ impl Node<'_> {
                 }
             }
             Node::Leaf(s) => {
+                let source = match s {
+                    Cow::Borrowed(source) => Some(*source),
+                    Cow::Owned(_) => None,
+                };
                 for path in paths {
-                    path.push_str(s);
+                    path.push_leaf(s, source);
                 }
             }
             Node::Branch(nodes) => {
                 let mut sub_paths = vec![];
                 for choice in nodes {
-                    let mut sub = vec!["".to_string()];
+                    let mut sub = vec![SourcedText::default()];
                     choice.collect_element_sequences(&mut sub);
                     sub_paths.append(&mut sub);
                 }
@@ -34,7 +43,7 @@ impl Node<'_> {
                 for path in paths.drain(..) {
                     for sub in &sub_paths {
                         let mut path = path.clone();
-                        path.push_str(sub);
+                        path.append(sub);
                         new_paths.push(path);
                     }
                 }
@@ -1,4 +1,59 @@
-#[derive(Default)]
-pub struct PhonoRuleParseOpts {}
+use crate::phonology::{
+    rule::ApplicationMode,
+    segment::{NaturalClasses, SegmentTable},
+    string::{EmptySyllablePolicy, SyllabifyOpts, WeightOpts},
+};
 
-impl PhonoRuleParseOpts {}
+#[derive(Debug, Clone, Default)]
+pub struct PhonoRuleParseOpts {
+    /// what happens to the features of syllables emptied by deletions
+    pub empty_syllables: EmptySyllablePolicy,
+    /// application mode of the rules without a mode prefix
+    pub mode: ApplicationMode,
+    /// when to rebuild the syllables of the string while applying rules
+    pub resyllabify: Resyllabify,
+    /// the syllabifier options used to resyllabify
+    pub syllabify: SyllabifyOpts,
+    /// natural classes usable in the rules, on top of the classes of the current segment table.
+    /// A class with the name of a table class replaces it.
+    pub classes: NaturalClasses,
+    /// what to do with the segments of the rules' outputs that break the feature geometry
+    pub geometry: GeometryCheck,
+    /// how the weights of syllables are counted, for patterns like `σ[+heavy]`
+    pub weight: WeightOpts,
+}
+
+impl PhonoRuleParseOpts {
+    /// the classes of the current segment table, with `classes`
+    pub fn all_classes(&self) -> NaturalClasses {
+        let mut classes = SegmentTable::current().classes.clone();
+        classes.extend(&self.classes);
+        classes
+    }
+}
+
+/// How a rule set checks the output of each rule against the feature geometry (see
+/// `FEATURE_GEOMETRY`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GeometryCheck {
+    /// don't check
+    #[default]
+    Off,
+    /// set the features dominated by negative nodes to `NA`
+    Normalize,
+    /// fail on contradictions, like `[+high+low]`
+    Validate,
+}
+
+/// When to rebuild the syllables of a string (see `PhonoString::syllabify`) while applying a rule
+/// set. Stress stays on the syllable that keeps the stressed nucleus.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Resyllabify {
+    /// keep the syllables as the rules left them
+    #[default]
+    Never,
+    /// after each rule, so later rules see the new syllables
+    AfterEachRule,
+    /// once, after the last rule
+    AtEnd,
+}
@@ -1,32 +1,58 @@
+use std::ops::RangeInclusive;
+
 use crate::phonology::feature::FeatureState;
+use crate::phonology::foot::{FOOT_BOUNDARY, PHRASE_BOUNDARY, parse_foot_features};
 use crate::phonology::rule::parse::elem::{Element, ElementSequence};
 use crate::phonology::rule::{SegmentInfo, SyllableInfo};
-use crate::phonology::segment::parse_segment;
-use crate::phonology::syllable::SyllableFeatures;
+use crate::phonology::segment::{
+    NaturalClasses, Notation, SegmentFeatures, parse_feature_value, parse_segment_in,
+};
+use crate::phonology::string::{
+    Weight, parse_constituent, parse_weight_class, weight_class_intersection,
+};
+use crate::phonology::syllable::{
+    PRIMARY, STRESS, SYL_FEATURE_NAMES, SYLLABLE_SYMBOL, SyllableFeatures, Tone, parse_tone,
+    split_tone_diacritic,
+};
 use nom::IResult;
 use nom::Parser;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::character::complete::{digit1, one_of};
-use nom::combinator::{map, map_res, opt};
-use nom::sequence::preceded;
+use nom::character::complete::{char, digit1, one_of, space0};
+use nom::combinator::{map, map_res, not, opt, value};
+use nom::multi::separated_list1;
+use nom::sequence::{delimited, preceded, terminated};
 
 /// Parse a segment element in a phonological rule
 /// like parse_segment, but tags can be added: C_1 means a consonant, with segment tagged "1"
-fn parse_segment_elem(input: &str) -> IResult<&str, Element> {
+///
+/// A syllable position can follow the segment, like `C[+coda]` for consonants in codas. Alone,
+/// like `[+coda]`, it stands for any segment in that position.
+fn parse_segment_elem<'a>(
+    input: &'a str,
+    notation: Notation,
+    classes: &NaturalClasses,
+) -> IResult<&'a str, Element> {
+    let segment = alt((
+        (
+            |input| parse_segment_in(input, notation, classes),
+            opt(parse_constituent),
+        ),
+        map(parse_constituent, |position| {
+            (SegmentFeatures::new_undef(), Some(position))
+        }),
+    ));
     let parser = (
-        parse_segment,
+        segment,
         opt(preceded(tag("_"), map_res(digit1, str::parse))),
     );
-    let mut parser = map(parser, |(seg_features, tag)| {
+    let mut parser = map(parser, |((seg_features, position), tag)| {
         Element::Features(
-            SyllableInfo {
-                tag: None,
-                features: SyllableFeatures::new_undef(),
-            },
+            SyllableInfo::new(None, SyllableFeatures::new_undef()),
             SegmentInfo {
                 tag,
                 features: seg_features,
+                position,
             },
         )
     });
@@ -34,66 +60,261 @@ fn parse_segment_elem(input: &str) -> IResult<&str, Element> {
     parser.parse(input)
 }
 
-pub fn parse_bound_elem(input: &str) -> IResult<&str, Element> {
-    let parser = one_of("#$.");
+/// Parse a segment element with a tone diacritic, like "á". The tone goes to the element's
+/// syllable.
+fn parse_toned_segment_elem<'a>(
+    input: &'a str,
+    notation: Notation,
+    classes: &NaturalClasses,
+) -> IResult<&'a str, Element> {
+    let fail = || nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Verify));
+    let (cluster_len, untoned, tone) = split_tone_diacritic(input).ok_or_else(fail)?;
 
-    let mut parser = map(parser, |x| {
-        if x == '#' {
-            Element::WordBoundary
-        } else {
-            Element::SyllableBoundary
+    // parse the segment without its tone diacritic, then find where it ends in `input`
+    let untoned_input = untoned.clone() + &input[cluster_len..];
+    let (rest, elem) = parse_segment_elem(&untoned_input, notation, classes).map_err(|_| fail())?;
+    let consumed = untoned_input.len() - rest.len();
+    if consumed < untoned.len() {
+        return Err(fail());
+    }
+    let remaining = &input[cluster_len + consumed - untoned.len()..];
+
+    Ok((remaining, with_tone(elem, tone)))
+}
+
+/// give `elem`'s syllable the tone `tone`
+fn with_tone(elem: Element, tone: Tone) -> Element {
+    match elem {
+        Element::Features(mut syl, seg) => {
+            syl.features.tone = Some(tone);
+            Element::Features(syl, seg)
         }
-    });
+        elem => elem,
+    }
+}
+
+/// Parse a syllable feature set after the syllable symbol, like `σ[+stress]` or `σ[+heavy]`. The
+/// features go to the syllable of the neighbouring segments, or stand for a whole syllable if it
+/// has no segments. `σ` alone is any syllable. Weight classes narrow each other, so
+/// `σ[+heavy-superheavy]` only matches heavy syllables.
+pub fn parse_syllable_elem(input: &str) -> IResult<&str, Element> {
+    let feature = alt((
+        map(parse_weight_class, SyllableFeature::Weight),
+        map(
+            (parse_feature_value, parse_syllable_feature_name),
+            |(state, feature)| SyllableFeature::Feature(state, feature),
+        ),
+    ));
+    let mut parser = preceded(
+        char(SYLLABLE_SYMBOL),
+        opt(delimited(
+            tag("["),
+            separated_list1(space0, feature),
+            tag("]"),
+        )),
+    );
+
+    let (rest, features) = parser.parse(input)?;
+    let mut syllable = SyllableInfo::new(None, SyllableFeatures::new_undef());
+    syllable.explicit = true;
+    for feature in features.into_iter().flatten() {
+        match feature {
+            SyllableFeature::Weight(class) => {
+                syllable.weight = Some(match syllable.weight {
+                    Some(weight) => weight_class_intersection(weight, class),
+                    None => class,
+                });
+            }
+            SyllableFeature::Feature(state, feature) => syllable.features.features[feature] = state,
+        }
+    }
+    // unstressed syllables have no primary stress, like `SyllableFeatures::UNSTRESSED`
+    let features = &mut syllable.features.features;
+    if features[STRESS as usize] == FeatureState::NEG
+        && features[PRIMARY as usize] == FeatureState::UNDEF
+    {
+        features[PRIMARY as usize] = FeatureState::NA;
+    }
+    Ok((rest, Element::SyllableFeatures(syllable)))
+}
+
+/// A feature in a syllable feature set
+#[derive(Clone)]
+enum SyllableFeature {
+    Weight(RangeInclusive<Weight>),
+    Feature(FeatureState, usize),
+}
+
+fn parse_syllable_feature_name(input: &str) -> IResult<&str, usize> {
+    SYL_FEATURE_NAMES
+        .iter()
+        .position(|name| input.starts_with(name))
+        .map(|i| (&input[SYL_FEATURE_NAMES[i].len()..], i))
+        .ok_or(nom::Err::Error(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Tag,
+        )))
+}
+
+/// Parse a stress mark of `notation`, like `ˈ`, returning the stress it marks
+pub fn parse_stress_mark(input: &str, notation: Notation) -> IResult<&str, SyllableFeatures> {
+    let stress = input
+        .chars()
+        .next()
+        .and_then(|mark| Some((mark, notation.stress_of_mark(mark)?)));
+    match stress {
+        Some((mark, stress)) => Ok((&input[mark.len_utf8()..], stress)),
+        None => Err(nom::Err::Error(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Char,
+        ))),
+    }
+}
+
+/// Parse a boundary: `‖` (or `||`) between phrases, `#` between words, `|` between feet, and `$`
+/// (or `.`) between syllables
+pub fn parse_bound_elem(input: &str) -> IResult<&str, Element> {
+    let mut parser = alt((
+        value(
+            Element::PhraseBoundary,
+            alt((tag(PHRASE_BOUNDARY), tag("||"))),
+        ),
+        value(Element::WordBoundary, tag("#")),
+        // `|\` is a click in X-SAMPA
+        value(
+            Element::FootBoundary,
+            terminated(tag(FOOT_BOUNDARY), not(tag("\\"))),
+        ),
+        value(Element::SyllableBoundary, one_of("$.")),
+    ));
 
     parser.parse(input)
 }
 
-/// Parse a elem which may be tagged segment, or boundary.
-pub fn parse_rule_elem(input: &str) -> IResult<&str, Element> {
-    let mut parser = alt((parse_segment_elem, parse_bound_elem));
+/// Parse a elem which may be tagged segment, or boundary. Natural classes are those of
+/// `classes`.
+pub fn parse_rule_elem<'a>(input: &'a str, classes: &NaturalClasses) -> IResult<&'a str, Element> {
+    let mut parser = alt((
+        |i| parse_segment_elem(i, Notation::Ipa, classes),
+        parse_bound_elem,
+    ));
 
     parser.parse(input)
 }
 
 /// parse a sequence of segments or boundaries ex: "es#ma.tan"
 ///
-/// A `'` before a syllable marks it as stressed: all segments in that syllable
-/// receive `SyllableFeatures::new([POS])` in their SyllableInfo. The flag resets
-/// at every subsequent boundary.
-pub fn parse_rule_elems(input: &str) -> IResult<&str, ElementSequence> {
+/// A `ˈ` (or `'`) before a syllable gives it primary stress: all segments in that syllable
+/// receive `SyllableFeatures::PRIMARY_STRESS` in their SyllableInfo. Likewise, `ˌ` (or `,`) gives
+/// it secondary stress. The stress resets at every subsequent boundary.
+///
+/// A tone in Chao tone letters or superscript numbers after a segment is the tone of that
+/// segment's syllable, like in "ma˨˩˦" or "ma²¹⁴". So is a tone diacritic, like in "mǎ".
+///
+/// `Σ[...]` gives its features to the foot of the following segments, like `Σ[+trochaic]`, and
+/// `σ[...]` to their syllable, like `σ[+heavy]`.
+///
+/// The symbols and stress marks are those of `notation`. In IPA, segments within quotes are in
+/// X-SAMPA, ex: "\"tS\"a". Quotes only select X-SAMPA in IPA, since `"` is the X-SAMPA stress
+/// mark.
+pub fn parse_rule_elems_in<'a>(
+    input: &'a str,
+    notation: Notation,
+    classes: &NaturalClasses,
+) -> IResult<&'a str, ElementSequence> {
+    let (rest, elements) = parse_rule_elems_with_text(input, notation, classes)?;
+    let elements = elements.into_iter().map(|(elem, _)| elem).collect();
+    Ok((rest, ElementSequence::new(elements)))
+}
+
+/// like `parse_rule_elems_in`, with the text of each element in `input`, like `a˥` for a toned
+/// segment
+pub(crate) fn parse_rule_elems_with_text<'a>(
+    input: &'a str,
+    notation: Notation,
+    classes: &NaturalClasses,
+) -> IResult<&'a str, Vec<(Element, &'a str)>> {
     // empty sequence is a null symbol
     if let Ok((rest, _)) = one_of::<_, _, nom::error::Error<&str>>("∅Ø")(input) {
-        return Ok((rest, ElementSequence::new(vec![])));
+        return Ok((rest, vec![]));
     }
 
-    let mut elements: Vec<Element> = vec![];
+    // the elements, with the start and end of their text in `input`
+    let mut elements: Vec<(Element, usize, usize)> = vec![];
+    let offset = |rest: &str| input.len() - rest.len();
     let mut remaining = input;
-    let mut syl_stressed = false;
+    let mut syl_stress: Option<SyllableFeatures> = None;
+    // within quotes, in X-SAMPA
+    let mut quoted = false;
 
     loop {
-        // stressed syllable boundary: sets stress flag for following segments
-        if let Ok((rest, _)) = tag::<_, _, nom::error::Error<&str>>("'")(remaining) {
-            elements.push(Element::SyllableBoundary);
-            syl_stressed = true;
+        if notation == Notation::Ipa
+            && let Some(rest) = remaining.strip_prefix('"')
+        {
+            quoted = !quoted;
             remaining = rest;
             continue;
         }
+        let seg_notation = if quoted { Notation::XSampa } else { notation };
 
-        // other boundary: resets stress flag
+        // stressed syllable boundary: sets the stress of the following segments
+        if !quoted && let Ok((rest, stress)) = parse_stress_mark(remaining, notation) {
+            elements.push((Element::SyllableBoundary, offset(remaining), offset(rest)));
+            syl_stress = Some(stress);
+            remaining = rest;
+            continue;
+        }
+
+        // foot features: go to the foot of the following segments
+        if !quoted && let Ok((rest, foot)) = parse_foot_features(remaining) {
+            elements.push((Element::FootFeatures(foot), offset(remaining), offset(rest)));
+            remaining = rest;
+            continue;
+        }
+
+        // syllable features: go to the syllable of the following segments
+        if !quoted && let Ok((rest, elem)) = parse_syllable_elem(remaining) {
+            elements.push((elem, offset(remaining), offset(rest)));
+            remaining = rest;
+            continue;
+        }
+
+        // other boundary: resets the stress
         if let Ok((rest, elem)) = parse_bound_elem(remaining) {
-            elements.push(elem);
-            syl_stressed = false;
+            elements.push((elem, offset(remaining), offset(rest)));
+            syl_stress = None;
+            remaining = rest;
+            continue;
+        }
+
+        // tone: goes to the syllable of the previous segment
+        if let Ok((rest, tone)) = parse_tone(remaining) {
+            match elements.pop() {
+                Some((elem @ Element::Features(_, _), start, _)) => {
+                    elements.push((with_tone(elem, tone), start, offset(rest)))
+                }
+                elem => {
+                    elements.extend(elem);
+                    break;
+                }
+            }
             remaining = rest;
             continue;
         }
 
         // segment: apply current syllable stress to its SyllableInfo
-        if let Ok((rest, elem)) = parse_segment_elem(remaining) {
+        let segment = if quoted {
+            parse_segment_elem(remaining, seg_notation, classes)
+        } else {
+            parse_toned_segment_elem(remaining, seg_notation, classes)
+                .or_else(|_| parse_segment_elem(remaining, seg_notation, classes))
+        };
+        if let Ok((rest, elem)) = segment {
             if let Element::Features(mut syl, seg) = elem {
-                if syl_stressed {
-                    syl.features = SyllableFeatures::new([FeatureState::POS]);
+                if let Some(stress) = &syl_stress {
+                    syl.features.features = stress.features;
                 }
-                elements.push(Element::Features(syl, seg));
+                elements.push((Element::Features(syl, seg), offset(remaining), offset(rest)));
             }
             remaining = rest;
             continue;
@@ -102,6 +323,13 @@ pub fn parse_rule_elems(input: &str) -> IResult<&str, ElementSequence> {
         break;
     }
 
+    if quoted {
+        // unclosed quote
+        return Err(nom::Err::Error(nom::error::Error::new(
+            remaining,
+            nom::error::ErrorKind::Char,
+        )));
+    }
     if elements.is_empty() {
         return Err(nom::Err::Error(nom::error::Error::new(
             input,
@@ -109,5 +337,9 @@ pub fn parse_rule_elems(input: &str) -> IResult<&str, ElementSequence> {
         )));
     }
 
-    Ok((remaining, ElementSequence::new(elements)))
+    let elements = elements
+        .into_iter()
+        .map(|(elem, start, end)| (elem, &input[start..end]))
+        .collect();
+    Ok((remaining, elements))
 }
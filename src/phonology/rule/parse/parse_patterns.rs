@@ -3,38 +3,52 @@ use std::iter::zip;
 use nom::{
     IResult, Parser as _,
     branch::alt,
-    bytes::complete::tag,
+    bytes::complete::{tag, take_till1},
     character::complete::{digit1, one_of, space0, space1},
-    combinator::{map, opt, recognize, verify},
+    combinator::{map, opt, recognize, success, value, verify},
     multi::{many1, separated_list1},
-    sequence::{delimited, preceded, separated_pair},
+    sequence::{delimited, preceded, separated_pair, terminated},
 };
 
 use crate::phonology::{
+    foot::parse_foot_features,
     rule::{
-        PhonoRuleParseOpts,
+        ApplicationMode, PhonoRuleParseOpts,
         parse::{
-            parse_elem::{parse_bound_elem, parse_rule_elem},
+            parse_elem::{
+                parse_bound_elem, parse_rule_elem, parse_stress_mark, parse_syllable_elem,
+            },
             pattern::{Pattern, RulePatterns},
         },
     },
     segment::{
-        parse_ipa_base, parse_ipa_diacritic, parse_natural_class, parse_segment_feature_set,
+        NaturalClass, NaturalClasses, Notation, parse_ipa_base, parse_ipa_diacritic,
+        parse_natural_class_in, parse_segment_feature_set,
     },
+    string::parse_constituent,
+    syllable::parse_tone_mark,
 };
 
 pub fn parse_rule_patterns(
     rule: &str,
-    _opts: PhonoRuleParseOpts,
+    opts: PhonoRuleParseOpts,
 ) -> IResult<&str, RulePatterns<'_>> {
-    let input = separated_list1(space1, parse_rule_pattern);
-    // output has no branching
-    let output = separated_list1(space1, parse_output);
+    let classes = &opts.all_classes();
+    let input = separated_list1(space1, |i| parse_rule_pattern(i, classes));
+    // output has no branching. an empty output is a deletion, like `∅`
+    let output = alt((
+        separated_list1(space1, |i| parse_output(i, classes)),
+        success(vec![""]),
+    ));
     let arrow = alt((tag("->"), tag("→")));
     let inner_rule = separated_pair(input, delimited(space0, arrow, space0), output);
     let context = preceded(
         delimited(space0, tag("/"), space0),
-        separated_pair(opt(parse_rule_pattern), tag("_"), opt(parse_rule_pattern)),
+        separated_pair(
+            opt(|i| parse_rule_pattern(i, classes)),
+            tag("_"),
+            opt(|i| parse_rule_pattern(i, classes)),
+        ),
     );
     let parser = (inner_rule, opt(context));
     // number of input choices should match number of output choices
@@ -64,10 +78,30 @@ pub fn parse_rule_patterns(
     Ok((remainder, rule))
 }
 
-pub fn parse_rule_elem_branch(input: &str) -> IResult<&str, Pattern<'_>> {
+/// Parse the application mode prefix of a rule, like `ltr:`
+pub fn parse_application_mode(input: &str) -> IResult<&str, ApplicationMode> {
+    let modes = [
+        ApplicationMode::Simultaneous,
+        ApplicationMode::LeftToRight,
+        ApplicationMode::RightToLeft,
+        ApplicationMode::Iterative,
+    ];
+    let mode = alt(modes.map(|mode| value(mode, tag(mode.prefix()))));
+    let mut parser = terminated(mode, (tag(":"), space0));
+
+    parser.parse(input)
+}
+
+pub fn parse_rule_elem_branch<'a>(
+    input: &'a str,
+    classes: &NaturalClasses,
+) -> IResult<&'a str, Pattern<'a>> {
     let parser = delimited(
         tag("{"),
-        separated_list1(tag(","), delimited(space0, parse_rule_pattern, space0)),
+        separated_list1(
+            tag(","),
+            delimited(space0, |i| parse_rule_pattern(i, classes), space0),
+        ),
         tag("}"),
     );
     let mut parser = map(parser, Pattern::branch);
@@ -75,8 +109,11 @@ pub fn parse_rule_elem_branch(input: &str) -> IResult<&str, Pattern<'_>> {
     parser.parse(input)
 }
 
-fn parse_rule_elem_opt(input: &str) -> IResult<&str, Pattern<'_>> {
-    let parser = delimited(tag("("), parse_rule_pattern, tag(")"));
+fn parse_rule_elem_opt<'a>(
+    input: &'a str,
+    classes: &NaturalClasses,
+) -> IResult<&'a str, Pattern<'a>> {
+    let parser = delimited(tag("("), |i| parse_rule_pattern(i, classes), tag(")"));
     let mut parser = map(parser, |tree| {
         // optional is just a branch between `tree` and `null`
         Pattern::optional(tree)
@@ -85,33 +122,83 @@ fn parse_rule_elem_opt(input: &str) -> IResult<&str, Pattern<'_>> {
     parser.parse(input)
 }
 
+/// a natural class defined by a list of segments, which is a branch between its segments.
+/// ex: "P" for `P = {p,t,k}` is like "{p,t,k}"
+fn parse_class_branch<'a>(
+    input: &'a str,
+    classes: &NaturalClasses,
+) -> IResult<&'a str, Pattern<'a>> {
+    match classes.longest_prefix(input) {
+        Some((name, NaturalClass::Segments(segments))) => {
+            let leaves = segments.iter().cloned().map(Pattern::owned_leaf).collect();
+            Ok((&input[name.len()..], Pattern::branch(leaves)))
+        }
+        _ => Err(nom::Err::Error(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Verify,
+        ))),
+    }
+}
+
 // parse a segment, part of a segment, or boundary.
-fn parse_rule_elem_part(input: &str) -> IResult<&str, Pattern<'_>> {
+fn parse_rule_elem_part<'a>(
+    input: &'a str,
+    classes: &NaturalClasses,
+) -> IResult<&'a str, Pattern<'a>> {
     let part = alt((
         recognize(parse_ipa_base),
         recognize(parse_ipa_diacritic),
-        recognize(parse_natural_class),
+        parse_tone_mark,
+        recognize(|i| parse_natural_class_in(i, classes)),
         recognize(parse_bound_elem),
+        recognize(parse_foot_features),
+        recognize(parse_syllable_elem),
+        parse_rule_stress_mark,
+        parse_xsampa_run,
         preceded(tag("_"), digit1),
         delimited(tag("["), recognize(parse_segment_feature_set), tag("]")),
+        recognize(parse_constituent),
     ));
     let mut parser = map(recognize(many1(part)), Pattern::leaf);
 
     parser.parse(input)
 }
 
+/// recognize a stress mark. `,` separates branches, so secondary stress is written `ˌ` in rules.
+fn parse_rule_stress_mark(input: &str) -> IResult<&str, &str> {
+    let stress_mark = verify(
+        |i| parse_stress_mark(i, Notation::Ipa),
+        |_| !input.starts_with(','),
+    );
+    let mut parser = recognize(stress_mark);
+
+    parser.parse(input)
+}
+
+/// recognize segments in X-SAMPA, within quotes. ex: "\"tS_1\""
+fn parse_xsampa_run(input: &str) -> IResult<&str, &str> {
+    let inner = take_till1(|c: char| c == '"' || c.is_whitespace());
+    let mut parser = recognize(delimited(tag("\""), inner, tag("\"")));
+
+    parser.parse(input)
+}
+
 /// parse a null symbol (Ø or ∅)
 pub fn parse_elem_null(input: &str) -> IResult<&str, Pattern<'_>> {
     let mut parser = map(one_of("∅Ø"), |_| Pattern::null());
     parser.parse(input)
 }
 
-pub fn parse_rule_pattern(input: &str) -> IResult<&str, Pattern<'_>> {
+pub fn parse_rule_pattern<'a>(
+    input: &'a str,
+    classes: &NaturalClasses,
+) -> IResult<&'a str, Pattern<'a>> {
     let mut parser = map(
         many1(alt((
-            parse_rule_elem_part,
-            parse_rule_elem_branch,
-            parse_rule_elem_opt,
+            |i| parse_class_branch(i, classes),
+            |i| parse_rule_elem_part(i, classes),
+            |i| parse_rule_elem_branch(i, classes),
+            |i| parse_rule_elem_opt(i, classes),
             parse_elem_null,
         ))),
         |mut x| {
@@ -126,10 +213,17 @@ pub fn parse_rule_pattern(input: &str) -> IResult<&str, Pattern<'_>> {
     parser.parse(input)
 }
 
-fn parse_output(input: &str) -> IResult<&str, &str> {
+fn parse_output<'a>(input: &'a str, classes: &NaturalClasses) -> IResult<&'a str, &'a str> {
     // either a sequence of elements, or a null symbol
     let mut parser = alt((
-        recognize(many1(parse_rule_elem)),
+        recognize(many1(alt((
+            recognize(|i| parse_rule_elem(i, classes)),
+            parse_tone_mark,
+            recognize(parse_foot_features),
+            recognize(parse_syllable_elem),
+            parse_rule_stress_mark,
+            parse_xsampa_run,
+        )))),
         recognize(parse_elem_null),
     ));
     parser.parse(input)
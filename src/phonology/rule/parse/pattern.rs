@@ -1,4 +1,4 @@
-use std::vec;
+use std::{borrow::Cow, vec};
 
 use crate::phonology::rule::parse::node::Node;
 
@@ -16,7 +16,14 @@ impl<'a> Pattern<'a> {
     /// Single leaf node
     pub fn leaf(elem: &'a str) -> Self {
         Self {
-            root: Node::Leaf(elem),
+            root: Node::Leaf(Cow::Borrowed(elem)),
+        }
+    }
+
+    /// Single leaf node, with text that isn't in the rule
+    pub fn owned_leaf(elem: String) -> Self {
+        Self {
+            root: Node::Leaf(Cow::Owned(elem)),
         }
     }
 
@@ -44,8 +51,8 @@ impl<'a> Pattern<'a> {
     }
 
     /// lists all possible element sequences, taking each branch combination.
-    pub fn enumerate_branches(self) -> Vec<String> {
-        let mut results: Vec<String> = vec!["".to_string()]; // start with one empty path
+    pub fn enumerate_branches(self) -> Vec<SourcedText<'a>> {
+        let mut results = vec![SourcedText::default()]; // start with one empty path
 
         self.root.collect_element_sequences(&mut results);
 
@@ -61,27 +68,99 @@ pub struct RulePatterns<'a> {
     pub(crate) post_context: Option<Pattern<'a>>,
 }
 
+/// The text of one choice of branches of a pattern, with the parts of the rule it comes from, so
+/// that errors in the text can point at the rule
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourcedText<'a> {
+    text: String,
+    /// the start of each leaf in `text`, and the leaf in the rule, or None for text that isn't in
+    /// the rule, like the definition of a natural class
+    leaves: Vec<(usize, Option<&'a str>)>,
+}
+
+impl<'a> SourcedText<'a> {
+    /// The text of a part of the rule
+    pub fn from_source(source: &'a str) -> Self {
+        Self {
+            text: source.to_string(),
+            leaves: vec![(0, Some(source))],
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Append a leaf of a pattern: `text`, which is `source` in the rule if it comes from it
+    pub(crate) fn push_leaf(&mut self, text: &str, source: Option<&'a str>) {
+        self.leaves.push((self.text.len(), source));
+        self.text.push_str(text);
+    }
+
+    /// Append the text and leaves of `other`
+    pub(crate) fn append(&mut self, other: &Self) {
+        let offset = self.text.len();
+        self.leaves.extend(
+            other
+                .leaves
+                .iter()
+                .map(|(start, source)| (offset + start, *source)),
+        );
+        self.text.push_str(&other.text);
+    }
+
+    /// The part of the rule that `part` comes from, cut at the end of its leaf. `part` must be a
+    /// slice of the text, like the remainder of a parser. None if it isn't in the rule.
+    pub fn source_of(&self, part: &str) -> Option<&'a str> {
+        let offset = (part.as_ptr() as usize).checked_sub(self.text.as_ptr() as usize)?;
+        let (start, source) = self
+            .leaves
+            .iter()
+            .rev()
+            .find(|(start, _)| *start <= offset)?;
+        let source = (*source)?;
+        let relative = offset - start;
+        source.get(relative..(relative + part.len()).min(source.len()))
+    }
+}
+
+impl From<&str> for SourcedText<'_> {
+    /// Text that isn't in a rule
+    fn from(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            leaves: vec![],
+        }
+    }
+}
+
+impl PartialEq<&str> for SourcedText<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self.text == *other
+    }
+}
+
 /// a rule, no branching: input, output and context. unparsed elements.
 #[derive(Debug, PartialEq)]
-pub struct RuleStrings {
-    pub(crate) input: Vec<Vec<String>>,
-    pub(crate) output: Vec<String>,
-    pub(crate) pre_context: Vec<String>,
-    pub(crate) post_context: Vec<String>,
+pub struct RuleStrings<'a> {
+    pub(crate) input: Vec<Vec<SourcedText<'a>>>,
+    pub(crate) output: Vec<SourcedText<'a>>,
+    pub(crate) pre_context: Vec<SourcedText<'a>>,
+    pub(crate) post_context: Vec<SourcedText<'a>>,
 }
 
-impl RulePatterns<'_> {
-    pub fn enumerate(self) -> RuleStrings {
+impl<'a> RulePatterns<'a> {
+    pub fn enumerate(self) -> RuleStrings<'a> {
         let mut input_vec = vec![];
         let mut output_vec = vec![];
-        let mut pre_context_opts = vec!["".to_string()];
-        let mut post_context_opts = vec!["".to_string()];
+        let mut pre_context_opts = vec![SourcedText::default()];
+        let mut post_context_opts = vec![SourcedText::default()];
 
         for input in self.input {
             input_vec.push(input.enumerate_branches());
         }
         for output in self.output {
-            output_vec.push(output.to_string());
+            output_vec.push(SourcedText::from_source(output));
         }
         if let Some(pre) = self.pre_context {
             pre_context_opts = pre.enumerate_branches();
@@ -13,7 +13,7 @@ use crate::phonology::{
             pattern::{Pattern, RuleStrings},
         },
     },
-    segment::SegmentFeatures,
+    segment::{NaturalClasses, SegmentFeatures},
     syllable::SyllableFeatures,
 };
 
@@ -34,21 +34,23 @@ macro_rules! test_phono_rule_syntax {
 const VOWEL_SEG: SegmentFeatures = SegmentFeatures::from_features([
     POS, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF,
     UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF,
+    UNDEF,
 ]);
 const CONS_SEG: SegmentFeatures = SegmentFeatures::from_features([
     NEG, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF,
     UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF,
+    UNDEF,
 ]);
 const T_SEG: SegmentFeatures = SegmentFeatures::from_features([
     NEG, NEG, POS, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NEG, POS, POS, NEG,
-    NEG, NEG, NEG, NA, NA, NA, NA, NA,
+    NEG, NEG, NEG, NA, NA, NA, NA, NA, NEG,
 ]);
 const D_SEG: SegmentFeatures = SegmentFeatures::from_features([
     NEG, NEG, POS, NEG, NEG, NEG, NEG, NEG, NEG, NEG, POS, NEG, NEG, NEG, NEG, NEG, POS, POS, NEG,
-    NEG, NEG, NEG, NA, NA, NA, NA, NA,
+    NEG, NEG, NEG, NA, NA, NA, NA, NA, NEG,
 ]);
 
-const UNDEF_SYL: SyllableFeatures = SyllableFeatures::from_features([UNDEF]);
+const UNDEF_SYL: SyllableFeatures = SyllableFeatures::new_undef();
 
 #[test]
 fn test_rule_simple_multi_pattern() {
@@ -59,13 +61,13 @@ fn test_rule_simple_multi_pattern() {
         rule.input,
         vec![
             Pattern {
-                root: Node::Leaf("z")
+                root: Node::Leaf("z".into())
             },
             Pattern {
-                root: Node::Leaf("ʃ"),
+                root: Node::Leaf("ʃ".into()),
             },
             Pattern {
-                root: Node::Leaf("tʃ")
+                root: Node::Leaf("tʃ".into())
             }
         ]
     );
@@ -97,23 +99,32 @@ test_phono_rule_syntax!(
 
 #[test]
 fn test_parse_branch() {
-    let (remaining, pat) = parse_rule_elem_branch("{C, V,C } ").unwrap();
+    let (remaining, pat) =
+        parse_rule_elem_branch("{C, V,C } ", &NaturalClasses::builtin()).unwrap();
     assert_eq!(remaining, " ");
     assert_eq!(
         pat.root,
-        Node::Branch(vec![Node::Leaf("C"), Node::Leaf("V"), Node::Leaf("C")])
+        Node::Branch(vec![
+            Node::Leaf("C".into()),
+            Node::Leaf("V".into()),
+            Node::Leaf("C".into())
+        ])
     );
 }
 
 #[test]
 fn test_parse_pattern() {
-    let (remaining, pat) = parse_rule_pattern("{V[+ant-dist+cor], a}S ").unwrap();
+    let (remaining, pat) =
+        parse_rule_pattern("{V[+ant-dist+cor], a}S ", &NaturalClasses::builtin()).unwrap();
     assert_eq!(remaining, " ");
     assert_eq!(
         pat.root,
         Node::Sequence(vec![
-            Node::Branch(vec![Node::Leaf("V[+ant-dist+cor]"), Node::Leaf("a")]),
-            Node::Leaf("S")
+            Node::Branch(vec![
+                Node::Leaf("V[+ant-dist+cor]".into()),
+                Node::Leaf("a".into())
+            ]),
+            Node::Leaf("S".into())
         ])
     );
 }
@@ -121,14 +132,17 @@ fn test_parse_pattern() {
 #[test]
 fn test_enumerate_branches() {
     let rule = "{θ,t}a{i(t),Ø}";
-    let (remainder, rule) = parse_rule_pattern(rule).unwrap();
+    let (remainder, rule) = parse_rule_pattern(rule, &NaturalClasses::builtin()).unwrap();
     assert_eq!(remainder, "");
 
     let possibilities = rule.enumerate_branches();
     let mut expected = vec!["θait", "θai", "θa", "tait", "tai", "ta"];
     // test ignoring order
     for expected in expected.drain(..) {
-        let match_n = possibilities.iter().filter(|x| *x == expected).count();
+        let match_n = possibilities
+            .iter()
+            .filter(|x| x.as_str() == expected)
+            .count();
         assert_eq!(match_n, 1);
     }
 }
@@ -153,13 +167,13 @@ fn test_enumerate_rule() {
 #[test]
 fn test_parse_rule_strings() {
     let rule = RuleStrings {
-        input: vec![vec!["Vʃ".to_string()]],
-        output: vec!["Vbʲ".to_string()],
-        pre_context: vec!["C$".to_string()],
-        post_context: vec!["iː".to_string()],
+        input: vec![vec!["Vʃ".into()]],
+        output: vec!["Vbʲ".into()],
+        pre_context: vec!["C$".into()],
+        post_context: vec!["iː".into()],
     };
 
-    let rules = RuleElements::from_strings(rule).unwrap();
+    let rules = RuleElements::from_strings(rule, "", &NaturalClasses::builtin()).unwrap();
     assert_eq!(rules.len(), 1);
 
     let rule = &rules[0];
@@ -185,62 +199,53 @@ fn test_parse_rule_strings() {
         Element::Features(
             SyllableInfo {
                 tag: Some(tag_syl0_in),
-                features: _,
+                ..
             },
             SegmentInfo {
                 tag: Some(tag_seg_in),
-                features: _,
+                ..
             },
         ),
         Element::Features(
             SyllableInfo {
                 tag: Some(tag_syl1_in),
-                features: _,
-            },
-            SegmentInfo {
-                tag: None,
-                features: _,
+                ..
             },
+            SegmentInfo { tag: None, .. },
         ),
         Element::Features(
             SyllableInfo {
                 tag: Some(tag_syl0_out),
-                features: _,
+                ..
             },
             SegmentInfo {
                 tag: Some(tag_seg_out),
-                features: _,
+                ..
             },
         ),
         Element::Features(
             SyllableInfo {
                 tag: Some(tag_syl1_out),
-                features: _,
-            },
-            SegmentInfo {
-                tag: None,
-                features: _,
+                ..
             },
+            SegmentInfo { tag: None, .. },
         ),
         Element::Features(
             SyllableInfo {
                 tag: Some(tag_syl_prectx),
-                features: _,
+                ..
             },
             SegmentInfo {
                 tag: Some(tag_seg_prectx),
-                features: _,
+                ..
             },
         ),
         Element::Features(
             SyllableInfo {
                 tag: Some(tag_syl_postctx),
-                features: _,
-            },
-            SegmentInfo {
-                tag: None,
-                features: _,
+                ..
             },
+            SegmentInfo { tag: None, .. },
         ),
     ) = (input_0, input_1, output_0, output_1, pre_0, post_0)
     {
@@ -266,42 +271,24 @@ fn test_parse_rule_strings() {
     assert!(matches!(
         input_1,
         Element::Features(
-            SyllableInfo {
-                tag: Some(_),
-                features: _
-            },
-            SegmentInfo {
-                tag: None,
-                features: _
-            }
+            SyllableInfo { tag: Some(_), .. },
+            SegmentInfo { tag: None, .. }
         )
     ));
     // bʲ
     assert!(matches!(
         output_1,
         Element::Features(
-            SyllableInfo {
-                tag: Some(_),
-                features: _
-            },
-            SegmentInfo {
-                tag: None,
-                features: _
-            }
+            SyllableInfo { tag: Some(_), .. },
+            SegmentInfo { tag: None, .. }
         )
     ));
     // iː
     assert!(matches!(
         post_0,
         Element::Features(
-            SyllableInfo {
-                tag: Some(_),
-                features: _
-            },
-            SegmentInfo {
-                tag: None,
-                features: _
-            }
+            SyllableInfo { tag: Some(_), .. },
+            SegmentInfo { tag: None, .. }
         )
     ));
 
@@ -363,24 +350,20 @@ fn test_compile_rule() {
         ElementSequence::new(post_context_elems),
     )
     .unwrap();
-    let result = compile_rule(rule);
+    let result = compile_rule(rule).unwrap();
 
     let pat_segs = result.pattern.tree.segs();
     let rep_segs = result.replace_tree.segs();
 
-    // context C (non-complete): tagged in pattern and replace, same tag
+    // the context is only in the pattern, the replacement covers the input
+    assert_eq!(result.focus, 1..4, "focus should be the input segments");
+    assert_eq!(rep_segs.len(), 3, "replace should only contain the output");
+
+    // context C (non-complete): tagged in pattern
     assert!(
         pat_segs[0].0.tag.is_some(),
         "pattern context C should have a seg tag"
     );
-    assert!(
-        rep_segs[0].0.tag.is_some(),
-        "replace context C should have a seg tag"
-    );
-    assert_eq!(
-        pat_segs[0].0.tag, rep_segs[0].0.tag,
-        "context C should share tag across pattern and replace"
-    );
 
     // input/output V (non-complete): tagged, and pattern/replace share the same tag
     assert!(
@@ -388,7 +371,7 @@ fn test_compile_rule() {
         "pattern V should have a seg tag"
     );
     assert_eq!(
-        pat_segs[1].0.tag, rep_segs[1].0.tag,
+        pat_segs[1].0.tag, rep_segs[0].0.tag,
         "input and output V should share seg tag"
     );
 
@@ -398,23 +381,19 @@ fn test_compile_rule() {
         "complete t should not be tagged"
     );
     assert!(
-        rep_segs[2].0.tag.is_none(),
+        rep_segs[1].0.tag.is_none(),
         "complete d should not be tagged"
     );
 
     // V pre-assigned tag 1: preserved in both
     assert_eq!(pat_segs[3].0.tag, Some(1), "input V_1 should keep tag 1");
-    assert_eq!(rep_segs[3].0.tag, Some(1), "output V_1 should keep tag 1");
+    assert_eq!(rep_segs[2].0.tag, Some(1), "output V_1 should keep tag 1");
 
     // post-context d (complete): no tag
     assert!(
         pat_segs[4].0.tag.is_none(),
         "post-context d in pattern should not be tagged"
     );
-    assert!(
-        rep_segs[4].0.tag.is_none(),
-        "post-context d in replace should not be tagged"
-    );
 
     assert!(result.test_invariants());
 }
@@ -1,9 +1,10 @@
 use std::ops::Range;
 
+use crate::error::*;
 use crate::phonology::{
-    rule::{SegmentInfo, SyllableInfo, TaggedPhonoString},
+    rule::{SegmentInfo, SyllableInfo, TaggedPhonoString, TaggedProsodicTree},
     string::PhonoString,
-    tree::Depth3Tree,
+    tree::{Attach, Depth3Tree, Depth5Tree},
 };
 
 #[derive(Debug)]
@@ -15,41 +16,133 @@ pub struct PhonoStringPattern {
 }
 
 impl PhonoStringPattern {
+    /// A pattern of a single phrase from a tree of words, syllables and segments. Each word is a
+    /// single foot without features.
     pub fn new(
         tree: Depth3Tree<(), SyllableInfo, SegmentInfo>,
         left_bound: PatternBorder,
         right_bound: PatternBorder,
+    ) -> Self {
+        Self::from_tree(tree.into(), left_bound, right_bound)
+    }
+
+    /// A pattern from its whole prosodic tree
+    pub fn from_tree(
+        tree: TaggedProsodicTree,
+        left_bound: PatternBorder,
+        right_bound: PatternBorder,
     ) -> Self {
         Self {
-            tree: TaggedPhonoString::new(tree),
+            tree: TaggedPhonoString::from_tree(tree),
             left_bound,
             right_bound,
         }
     }
 }
 
-#[derive(Debug)]
-pub enum PatternBorder {
+/// The prosodic level of the boundary between two segments, from the lowest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BoundaryLevel {
+    /// the segments are in the same syllable
+    Segment,
+    Syllable,
+    Foot,
     Word,
-    StrictSyllable, // may only be a syllable boundary, not more or less
+    Phrase,
+}
+
+impl BoundaryLevel {
+    /// The boundary at the gap before leaf `gap` of a prosodic tree. The edges of the tree are
+    /// phrase boundaries.
+    pub fn at_gap<T0, T1, T2, T3, T4>(tree: &Depth5Tree<T0, T1, T2, T3, T4>, gap: usize) -> Self {
+        if gap == 0 || gap >= tree.len_4() {
+            return BoundaryLevel::Phrase;
+        }
+        let (left_syl, right_syl) = (tree.layer_4()[gap - 1].1, tree.layer_4()[gap].1);
+        if left_syl == right_syl {
+            return BoundaryLevel::Segment;
+        }
+        Self::between_syllables(tree, left_syl, right_syl)
+    }
+
+    /// The boundary between the syllables `left_syl` and `right_syl` of a prosodic tree
+    pub fn between_syllables<T0, T1, T2, T3, T4>(
+        tree: &Depth5Tree<T0, T1, T2, T3, T4>,
+        left_syl: usize,
+        right_syl: usize,
+    ) -> Self {
+        let (left_foot, right_foot) = (tree.layer_3()[left_syl].1, tree.layer_3()[right_syl].1);
+        if left_foot == right_foot {
+            return BoundaryLevel::Syllable;
+        }
+        let (left_word, right_word) = (tree.layer_2()[left_foot].1, tree.layer_2()[right_foot].1);
+        if left_word == right_word {
+            return BoundaryLevel::Foot;
+        }
+        if tree.layer_1()[left_word].1 == tree.layer_1()[right_word].1 {
+            BoundaryLevel::Word
+        } else {
+            BoundaryLevel::Phrase
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum PatternBorder {
+    Phrase,
+    Word,           // may be a word or phrase boundary
+    Foot,           // may only be a foot boundary, not a word boundary
+    StrictSyllable, // may be a syllable or foot boundary, not a word boundary
     StrictSegment,  // may only be a segment boundary, not more
-    SyllableOrWord, // may only be a syllable or word boundary
-    Any,            // may only be a segment, syllable, or word boundary
+    SyllableOrWord, // may be any boundary but a segment boundary
+    Any,            // may be any boundary
 }
 
 impl PatternBorder {
-    pub fn respects(&self, on_syllable_border: bool, on_word_border: bool) -> bool {
+    pub fn respects(&self, boundary: BoundaryLevel) -> bool {
         match self {
-            PatternBorder::Word => on_syllable_border && on_word_border,
-            PatternBorder::SyllableOrWord => on_syllable_border,
-            PatternBorder::StrictSyllable => on_syllable_border && !on_word_border,
-            PatternBorder::StrictSegment => !on_syllable_border,
+            PatternBorder::Phrase => boundary == BoundaryLevel::Phrase,
+            PatternBorder::Word => boundary >= BoundaryLevel::Word,
+            PatternBorder::Foot => boundary == BoundaryLevel::Foot,
+            PatternBorder::StrictSyllable => {
+                matches!(boundary, BoundaryLevel::Syllable | BoundaryLevel::Foot)
+            }
+            PatternBorder::StrictSegment => boundary == BoundaryLevel::Segment,
+            PatternBorder::SyllableOrWord => boundary > BoundaryLevel::Segment,
             PatternBorder::Any => true,
         }
     }
 }
 
+impl From<BoundaryLevel> for PatternBorder {
+    /// the border written with a boundary of `level`, like `$` for syllables
+    fn from(level: BoundaryLevel) -> Self {
+        match level {
+            BoundaryLevel::Segment => PatternBorder::StrictSegment,
+            BoundaryLevel::Syllable => PatternBorder::StrictSyllable,
+            BoundaryLevel::Foot => PatternBorder::Foot,
+            BoundaryLevel::Word => PatternBorder::Word,
+            BoundaryLevel::Phrase => PatternBorder::Phrase,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct PatternMatch {
+    /// the segments to replace. an empty range is an insertion point
     pub range: Range<usize>,
     pub replace_with: PhonoString,
+    /// for insertions, the syllable `replace_with` goes into
+    pub attach: Attach,
+}
+
+impl PatternMatch {
+    /// Replace the matched segments in `string`, or insert at the match for empty matches
+    pub fn apply(self, string: PhonoString) -> Result<PhonoString> {
+        if self.range.is_empty() {
+            string.insert(self.range.start, self.attach, self.replace_with)
+        } else {
+            string.replace_range(self.range, self.replace_with)
+        }
+    }
 }
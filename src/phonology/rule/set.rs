@@ -1,44 +1,299 @@
 use crate::phonology::{
     rule::{
-        PhonoRule,
-        parse::{PhonoRuleParseOpts, RuleElements, parse_rule_patterns},
+        Derivation, DerivationStep, PatternMatch, PhonoRule, RuleSource,
+        parse::{
+            GeometryCheck, PhonoRuleParseOpts, Resyllabify, RuleElements, parse_application_mode,
+            parse_rule_patterns,
+        },
     },
-    string::PhonoString,
+    segment::{NaturalClass, is_class_name},
+    string::{PhonoString, StressSystem},
+    tree::{Attach, Depth3Tree},
 };
 
 use super::compile::compile_rule;
 use crate::error::*;
 
+/// An ordered list of phonological rules, usually read from a rule file.
+///
+/// A rule file has one rule per line. Blank lines are ignored, and `#` starts a comment when it is
+/// at the start of a line or preceded by whitespace, and followed by whitespace or the end of the
+/// line, unless it is next to the arrow. This keeps word boundaries like `#_`, `_#`, `# -> ∅`
+/// or `$ -> #` usable in rules.
+///
+/// A rule can start with an application mode prefix (see `ApplicationMode`), like
+/// `ltr: V -> [αback] / V[αback]_`. Rules without a prefix use the mode in the parse options.
+///
+/// A line can also define a natural class for the rules after it (see `NaturalClass`), like
+/// `N = [+nasal]` or `P = {p,t,k,q}`, or assign stress between two rules (see `StressSystem`),
+/// like `stress: latin`.
 pub struct PhonoRuleSet {
     pub rule_text: String,
     pub rules: Vec<PhonoRule>,
+    /// the stress assignments between the rules, in order
+    pub stress_steps: Vec<StressStep>,
+    pub opts: PhonoRuleParseOpts,
+}
+
+/// A stress assignment in a rule set, from a line like `stress: latin`
+#[derive(Debug, Clone)]
+pub struct StressStep {
+    /// index of the rule the stress is assigned before. The stress steps after the last rule
+    /// have the number of rules.
+    pub before_rule: usize,
+    pub system: StressSystem,
+    pub source: Option<RuleSource>,
 }
 
 impl PhonoRuleSet {
-    pub fn apply(&self, mut string: PhonoString) -> PhonoString {
-        for rule in &self.rules {
-            let mut matches = rule.find(string.clone());
-            matches.sort_by(|a, b| b.range.start.cmp(&a.range.start));
-            for m in matches {
-                string = string.replace_range(m.range, m.replace_with).unwrap();
+    /// Apply each rule in order, each rule is applied to the output of the previous rule.
+    /// Syllables and words emptied by a rule are removed before the next rule. Syllables are
+    /// rebuilt according to the `resyllabify` option, and the output of each rule is checked
+    /// according to the `geometry` option. The stress steps run between the rules they were
+    /// written between.
+    pub fn apply(&self, mut string: PhonoString) -> Result<PhonoString> {
+        for (rule_idx, rule) in self.rules.iter().enumerate() {
+            for step in self.stress_steps_before(rule_idx) {
+                string = string.assign_stress(step.system, &self.opts.weight);
             }
+            string = self.apply_rule(rule, string, &mut vec![])?;
+        }
+        for step in self.stress_steps_before(self.rules.len()) {
+            string = string.assign_stress(step.system, &self.opts.weight);
         }
-        string
+        if self.opts.resyllabify == Resyllabify::AtEnd {
+            string = string.syllabify(&self.opts.syllabify);
+        }
+        Ok(string)
     }
 
-    #[allow(clippy::result_unit_err)] // TODO make error types
-    pub fn parse(input: &str, opts: PhonoRuleParseOpts) -> Result<Self> {
-        let (rem, patterns) = parse_rule_patterns(input, opts).map_err(Error::other)?;
-        let elements = RuleElements::from_strings(patterns.enumerate())?;
+    /// Apply each rule in order like `apply`, and keep the output of each rule along with the
+    /// matches it applied. With `Resyllabify::AtEnd`, the output of the last rule is
+    /// resyllabified in a last step, even without rules.
+    pub fn derive(&self, string: PhonoString) -> Result<Derivation> {
+        let mut steps: Vec<DerivationStep> = vec![];
+        for rule_idx in 0..=self.rules.len() {
+            for step in self.stress_steps_before(rule_idx) {
+                let input = steps.last().map_or(&string, |step| &step.output).clone();
+                let output = input.clone().assign_stress(step.system, &self.opts.weight);
+                steps.push(DerivationStep {
+                    rule_idx,
+                    source: step.source.clone(),
+                    resyllabification: false,
+                    matches: stress_changes(&input, &output),
+                    output,
+                });
+            }
+            let Some(rule) = self.rules.get(rule_idx) else {
+                break;
+            };
+            let input = steps.last().map_or(&string, |step| &step.output).clone();
+            let mut matches = vec![];
+            let output = self.apply_rule(rule, input, &mut matches)?;
+            steps.push(DerivationStep {
+                rule_idx,
+                source: rule.source.clone(),
+                resyllabification: false,
+                matches,
+                output,
+            });
+        }
+        if self.opts.resyllabify == Resyllabify::AtEnd {
+            let input = steps.last().map_or(&string, |step| &step.output).clone();
+            let output = input.clone().syllabify(&self.opts.syllabify);
+            steps.push(DerivationStep {
+                rule_idx: self.rules.len(),
+                source: None,
+                resyllabification: true,
+                matches: syllable_changes(&input, &output),
+                output,
+            });
+        }
 
-        if !rem.is_empty() {
-            return Err(Error::other(format!(
-                "Couldn't parse rule set \"{input}\", remainder was {rem}"
-            )));
+        Ok(Derivation {
+            input: string,
+            steps,
+        })
+    }
+
+    fn stress_steps_before(&self, rule_idx: usize) -> impl Iterator<Item = &StressStep> {
+        self.stress_steps
+            .iter()
+            .filter(move |step| step.before_rule == rule_idx)
+    }
+
+    fn apply_rule(
+        &self,
+        rule: &PhonoRule,
+        string: PhonoString,
+        applied: &mut Vec<PatternMatch>,
+    ) -> Result<PhonoString> {
+        let mut string = rule.apply_traced(string, self.opts.empty_syllables, applied)?;
+        match self.opts.geometry {
+            GeometryCheck::Off => {}
+            GeometryCheck::Normalize => string = string.normalize_features(),
+            GeometryCheck::Validate => string.validate_features()?,
+        }
+        if self.opts.resyllabify == Resyllabify::AfterEachRule {
+            return Ok(string.syllabify(&self.opts.syllabify));
+        }
+        Ok(string)
+    }
+
+    /// Parse a rule file: one rule or class definition per line, with comments and blank lines.
+    /// The classes defined in the file are added to the classes of `opts`.
+    pub fn parse(input: &str, mut opts: PhonoRuleParseOpts) -> Result<Self> {
+        let mut rules = vec![];
+        let mut stress_steps = vec![];
+        for (line_idx, line) in input.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(system) = line.strip_prefix("stress:") {
+                let system = StressSystem::parse(system)
+                    .map_err(|e| e.or_span(Span::of(input, system.trim())))?;
+                stress_steps.push(StressStep {
+                    before_rule: rules.len(),
+                    system,
+                    source: Some(RuleSource {
+                        line: line_idx + 1,
+                        text: line.to_string(),
+                    }),
+                });
+                continue;
+            }
+            if let Some((name, definition)) = parse_class_definition(line) {
+                let class = NaturalClass::parse(definition)
+                    .map_err(|e| e.or_span(Span::of(input, definition)))?;
+                opts.classes
+                    .define(name, class)
+                    .map_err(|e| e.or_span(Span::of(input, name)))?;
+                continue;
+            }
+
+            let line_rules = parse_rule(input, line, opts.clone())?;
+            let source = RuleSource {
+                line: line_idx + 1,
+                text: line.to_string(),
+            };
+            rules.extend(line_rules.into_iter().map(|rule| PhonoRule {
+                source: Some(source.clone()),
+                ..rule
+            }));
         }
+
         Ok(Self {
             rule_text: input.to_string(),
-            rules: elements.into_iter().map(compile_rule).collect(),
+            rules,
+            stress_steps,
+            opts,
         })
     }
 }
+
+/// Parse a single rule, with an optional mode prefix. Branching rules produce more than one
+/// `PhonoRule`. `rule` is a slice of `source`, the text of the rule file, which error spans refer
+/// to.
+fn parse_rule(source: &str, rule: &str, opts: PhonoRuleParseOpts) -> Result<Vec<PhonoRule>> {
+    let rule_span = Span::of(source, rule);
+    let (rule, mode) = match parse_application_mode(rule) {
+        Ok((rule, mode)) => (rule, mode),
+        Err(_) => (rule, opts.mode),
+    };
+    let classes = opts.all_classes();
+    let weight = opts.weight;
+    let (rem, patterns) = parse_rule_patterns(rule, opts).map_err(|e| match e {
+        nom::Err::Error(e) | nom::Err::Failure(e) => {
+            Error::rule_syntax("couldn't parse rule", Some(Span::of(source, e.input)))
+        }
+        nom::Err::Incomplete(_) => Error::rule_syntax("incomplete rule", Some(rule_span.clone())),
+    })?;
+    if !rem.is_empty() {
+        return Err(Error::rule_syntax(
+            format!("unexpected \"{rem}\""),
+            Some(Span::of(source, rem)),
+        ));
+    }
+
+    let elements = RuleElements::from_strings(patterns.enumerate(), source, &classes)
+        .map_err(|e| e.or_span(rule_span.clone()))?;
+    elements
+        .into_iter()
+        .map(|elements| {
+            let mut rule = compile_rule(elements).map_err(|e| e.or_span(rule_span.clone()))?;
+            rule.mode = mode;
+            rule.weight = weight;
+            Ok(rule)
+        })
+        .collect()
+}
+
+/// The syllables whose stress changed from `before` to `after`, as one match per syllable.
+/// Unstressing a syllable without stress isn't a change.
+fn stress_changes(before: &PhonoString, after: &PhonoString) -> Vec<PatternMatch> {
+    let stress_level =
+        |string: &PhonoString, syl_idx: usize| string.tree.layer_3()[syl_idx].0.stress_level();
+    (0..after.tree.len_3())
+        .filter(|syl_idx| stress_level(before, *syl_idx) != stress_level(after, *syl_idx))
+        .map(|syl_idx| syllable_match(after, syl_idx))
+        .collect()
+}
+
+/// The syllables of `after` that aren't syllables of `before`, which has the same segments, as
+/// one match per syllable
+fn syllable_changes(before: &PhonoString, after: &PhonoString) -> Vec<PatternMatch> {
+    let before_ranges: Vec<_> = (0..before.tree.len_3())
+        .map(|syl_idx| before.tree.children_4(syl_idx))
+        .collect();
+    (0..after.tree.len_3())
+        .filter(|syl_idx| !before_ranges.contains(&after.tree.children_4(*syl_idx)))
+        .map(|syl_idx| syllable_match(after, syl_idx))
+        .collect()
+}
+
+/// a match replacing the segments of syllable `syl_idx` of `string` with the syllable
+fn syllable_match(string: &PhonoString, syl_idx: usize) -> PatternMatch {
+    let range = string.tree.children_4(syl_idx);
+    let mut syllable = Depth3Tree::new();
+    syllable.push_depth_0(());
+    syllable.push_depth_1(string.tree.layer_3()[syl_idx].0.clone());
+    for (seg, _) in &string.tree.layer_4()[range.clone()] {
+        syllable.push_depth_2(seg.clone());
+    }
+    PatternMatch {
+        range,
+        replace_with: PhonoString::new(syllable),
+        attach: Attach::default(),
+    }
+}
+
+/// The class name and definition of a class definition line, like `N = [+nasal]`
+fn parse_class_definition(line: &str) -> Option<(&str, &str)> {
+    let (name, definition) = line.split_once('=')?;
+    let name = name.trim();
+    (is_class_name(name) && !definition.contains("->") && !definition.contains('→'))
+        .then(|| (name, definition.trim()))
+}
+
+/// Remove a trailing `#` comment from a rule file line.
+fn strip_comment(line: &str) -> &str {
+    let bytes = line.as_bytes();
+    for (idx, c) in line.char_indices() {
+        if c != '#' {
+            continue;
+        }
+        let after_space = idx == 0 || bytes[idx - 1].is_ascii_whitespace();
+        let before_space = bytes.get(idx + 1).is_none_or(|b| b.is_ascii_whitespace());
+        // the input or output of a rule, like `# -> ∅` or `$ -> #`
+        let (before, after) = (line[..idx].trim_end(), line[idx + 1..].trim_start());
+        let next_to_arrow = before.ends_with("->")
+            || before.ends_with('→')
+            || after.starts_with("->")
+            || after.starts_with('→');
+        if after_space && before_space && !next_to_arrow {
+            return &line[..idx];
+        }
+    }
+    line
+}
@@ -1,13 +1,15 @@
 use crate::{
     d3tree,
+    error::Error,
     phonology::{
         feature::FeatureState::*,
         rule::{
-            PatternBorder, PhonoRule, PhonoRuleParseOpts, PhonoRuleSet, PhonoStringPattern,
-            SegmentInfo, SyllableInfo, TaggedPhonoString,
+            ApplicationMode, GeometryCheck, PatternBorder, PhonoRule, PhonoRuleParseOpts,
+            PhonoRuleSet, PhonoStringPattern, Resyllabify, SegmentInfo, SyllableInfo,
+            TaggedPhonoString,
         },
-        segment::{SEG_FEATURE_COUNT, SegmentFeatures},
-        string::PhonoString,
+        segment::{NaturalClass, SEG_FEATURE_COUNT, SegmentFeatures, parse_segment},
+        string::{EmptySyllablePolicy, PhonoString, WeightOpts},
         syllable::SyllableFeatures,
     },
 };
@@ -15,36 +17,39 @@ use crate::{
 const VOWEL_SEG: SegmentFeatures = SegmentFeatures::from_features([
     POS, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF,
     UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF,
+    UNDEF,
 ]);
 const CONS_SEG: SegmentFeatures = SegmentFeatures::from_features([
     NEG, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF,
     UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF,
+    UNDEF,
 ]);
 const T_SEG: SegmentFeatures = SegmentFeatures::from_features([
     NEG, NEG, POS, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NEG, POS, POS, NEG,
-    NEG, NEG, NEG, NA, NA, NA, NA, NA,
+    NEG, NEG, NEG, NA, NA, NA, NA, NA, NEG,
 ]);
 const D_SEG: SegmentFeatures = SegmentFeatures::from_features([
     NEG, NEG, POS, NEG, NEG, NEG, NEG, NEG, NEG, NEG, POS, NEG, NEG, NEG, NEG, NEG, POS, POS, NEG,
-    NEG, NEG, NEG, NA, NA, NA, NA, NA,
+    NEG, NEG, NEG, NA, NA, NA, NA, NA, NEG,
 ]);
 const A_SEG: SegmentFeatures = SegmentFeatures::from_features([
     POS, NEG, NEG, POS, POS, NEG, POS, NEG, NEG, NEG, POS, NEG, NEG, NEG, NEG, NEG, NEG, NA, NA,
-    NA, NEG, POS, NEG, POS, NEG, NEG, NA,
+    NA, NEG, POS, NEG, POS, NEG, NEG, NA, NEG,
 ]);
 const I_SEG: SegmentFeatures = SegmentFeatures::from_features([
     POS, NEG, NEG, POS, POS, NEG, POS, NEG, NEG, NEG, POS, NEG, NEG, NEG, NEG, NEG, NEG, NA, NA,
-    NA, NEG, POS, POS, NEG, POS, NEG, POS,
+    NA, NEG, POS, POS, NEG, POS, NEG, POS, NEG,
 ]);
 const VOI_SEG: SegmentFeatures = SegmentFeatures::from_features([
     UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, POS, UNDEF, UNDEF, UNDEF,
     UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF,
+    UNDEF,
 ]);
 const UNDEF_SEG: SegmentFeatures =
     SegmentFeatures::from_features([UNDEF; SEG_FEATURE_COUNT as usize]);
 
-const UNSTRESSED: SyllableFeatures = SyllableFeatures::new([NEG]);
-const UNDEF_SYL: SyllableFeatures = SyllableFeatures::new([UNDEF]);
+const UNSTRESSED: SyllableFeatures = SyllableFeatures::UNSTRESSED;
+const UNDEF_SYL: SyllableFeatures = SyllableFeatures::new_undef();
 
 #[test]
 fn test_rule_simple() {
@@ -52,18 +57,18 @@ fn test_rule_simple() {
     // VtV => VV / (all in same syllable and word)
     let match_tree = d3tree![
         () => [
-            SyllableInfo {tag: Some(0), features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(1), features: VOWEL_SEG},
-                SegmentInfo {tag: None, features: T_SEG},
-                SegmentInfo {tag: Some(2), features: VOWEL_SEG},
+            SyllableInfo::new_tagged(0, UNDEF_SYL) => [
+                SegmentInfo::new_tagged(1, VOWEL_SEG),
+                SegmentInfo::new_untagged(T_SEG),
+                SegmentInfo::new_tagged(2, VOWEL_SEG),
             ]
         ]
     ];
     let replace_tree = d3tree![
         () => [
-            SyllableInfo {tag: Some(0), features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(1), features: UNDEF_SEG},
-                SegmentInfo {tag: Some(2), features: UNDEF_SEG},
+            SyllableInfo::new_tagged(0, UNDEF_SYL) => [
+                SegmentInfo::new_tagged(1, UNDEF_SEG),
+                SegmentInfo::new_tagged(2, UNDEF_SEG),
             ]
         ]
     ];
@@ -102,19 +107,19 @@ fn test_rule_modify_vowel() {
     // for example, [ati] becomes [adi]
     let match_tree = d3tree![
         () => [
-            SyllableInfo {tag: Some(0), features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(1), features: VOWEL_SEG},
-                SegmentInfo {tag: Some(2), features: CONS_SEG},
-                SegmentInfo {tag: Some(3), features: VOWEL_SEG},
+            SyllableInfo::new_tagged(0, UNDEF_SYL) => [
+                SegmentInfo::new_tagged(1, VOWEL_SEG),
+                SegmentInfo::new_tagged(2, CONS_SEG),
+                SegmentInfo::new_tagged(3, VOWEL_SEG),
             ]
         ]
     ];
     let replace_tree = d3tree![
         () => [
-            SyllableInfo {tag: Some(0), features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(1), features: VOWEL_SEG},
-                SegmentInfo {tag: Some(2), features: VOI_SEG},
-                SegmentInfo {tag: Some(3), features: VOWEL_SEG},
+            SyllableInfo::new_tagged(0, UNDEF_SYL) => [
+                SegmentInfo::new_tagged(1, VOWEL_SEG),
+                SegmentInfo::new_tagged(2, VOI_SEG),
+                SegmentInfo::new_tagged(3, VOWEL_SEG),
             ]
         ]
     ];
@@ -152,20 +157,20 @@ fn test_rule_new_syllable() {
     // VtV => V.V / (in same syllable and word, creates new syllable boundary)
     let match_tree = d3tree![
         () => [
-            SyllableInfo {tag: Some(0), features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(1), features: VOWEL_SEG},
-                SegmentInfo {tag: None, features: T_SEG},
-                SegmentInfo {tag: Some(2), features: VOWEL_SEG},
+            SyllableInfo::new_tagged(0, UNDEF_SYL) => [
+                SegmentInfo::new_tagged(1, VOWEL_SEG),
+                SegmentInfo::new_untagged(T_SEG),
+                SegmentInfo::new_tagged(2, VOWEL_SEG),
             ]
         ]
     ];
     let replace_tree = d3tree![
         () => [
-            SyllableInfo {tag: Some(0), features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(1), features: UNDEF_SEG},
+            SyllableInfo::new_tagged(0, UNDEF_SYL) => [
+                SegmentInfo::new_tagged(1, UNDEF_SEG),
             ],
-            SyllableInfo {tag: None, features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(2), features: UNDEF_SEG},
+            SyllableInfo::new_untagged(UNDEF_SYL) => [
+                SegmentInfo::new_tagged(2, UNDEF_SEG),
             ]
         ]
     ];
@@ -204,20 +209,20 @@ fn test_rule_across_syllable() {
     // V.V => VtV / (across syllable bound, removes it)
     let match_tree = d3tree![
         () => [
-            SyllableInfo {tag: Some(0), features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(0), features: VOWEL_SEG},
+            SyllableInfo::new_tagged(0, UNDEF_SYL) => [
+                SegmentInfo::new_tagged(0, VOWEL_SEG),
             ],
-            SyllableInfo {tag: Some(1), features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(1), features: VOWEL_SEG},
+            SyllableInfo::new_tagged(1, UNDEF_SYL) => [
+                SegmentInfo::new_tagged(1, VOWEL_SEG),
             ]
         ]
     ];
     let replace_tree = d3tree![
         () => [
-            SyllableInfo {tag: Some(0), features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(0), features: UNDEF_SEG},
-                SegmentInfo {tag: None, features: T_SEG},
-                SegmentInfo {tag: Some(1), features: UNDEF_SEG},
+            SyllableInfo::new_tagged(0, UNDEF_SYL) => [
+                SegmentInfo::new_tagged(0, UNDEF_SEG),
+                SegmentInfo::new_untagged(T_SEG),
+                SegmentInfo::new_tagged(1, UNDEF_SEG),
             ]
         ]
     ];
@@ -255,22 +260,22 @@ fn test_rule_new_word() {
     // VtV => V.V / (in same syllable and word, creates new syllable boundary)
     let match_tree = d3tree![
         () => [
-            SyllableInfo {tag: Some(0), features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(1), features: VOWEL_SEG},
-                SegmentInfo {tag: None, features: T_SEG},
-                SegmentInfo {tag: Some(2), features: VOWEL_SEG},
+            SyllableInfo::new_tagged(0, UNDEF_SYL) => [
+                SegmentInfo::new_tagged(1, VOWEL_SEG),
+                SegmentInfo::new_untagged(T_SEG),
+                SegmentInfo::new_tagged(2, VOWEL_SEG),
             ]
         ]
     ];
     let replace_tree = d3tree![
         () => [
-            SyllableInfo {tag: Some(0), features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(1), features: UNDEF_SEG},
+            SyllableInfo::new_tagged(0, UNDEF_SYL) => [
+                SegmentInfo::new_tagged(1, UNDEF_SEG),
             ]
         ],
         () => [
-            SyllableInfo {tag: None, features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(2), features: UNDEF_SEG},
+            SyllableInfo::new_untagged(UNDEF_SYL) => [
+                SegmentInfo::new_tagged(2, UNDEF_SEG),
             ]
         ]
     ];
@@ -311,22 +316,22 @@ fn test_rule_across_word() {
     // V.V => VtV / (across syllable bound, removes it)
     let match_tree = d3tree![
         () => [
-            SyllableInfo {tag: Some(0), features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(0), features: VOWEL_SEG},
+            SyllableInfo::new_tagged(0, UNDEF_SYL) => [
+                SegmentInfo::new_tagged(0, VOWEL_SEG),
             ],
         ],
         () => [
-            SyllableInfo {tag: Some(1), features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(1), features: VOWEL_SEG},
+            SyllableInfo::new_tagged(1, UNDEF_SYL) => [
+                SegmentInfo::new_tagged(1, VOWEL_SEG),
             ]
         ]
     ];
     let replace_tree = d3tree![
         () => [
-            SyllableInfo {tag: Some(0), features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(0), features: UNDEF_SEG},
-                SegmentInfo {tag: None, features: T_SEG},
-                SegmentInfo {tag: Some(1), features: UNDEF_SEG},
+            SyllableInfo::new_tagged(0, UNDEF_SYL) => [
+                SegmentInfo::new_tagged(0, UNDEF_SEG),
+                SegmentInfo::new_untagged(T_SEG),
+                SegmentInfo::new_tagged(1, UNDEF_SEG),
             ]
         ]
     ];
@@ -367,8 +372,8 @@ fn test_rule_syllable_border_1() {
     let pattern = PhonoStringPattern::new(
         d3tree![
             () => [
-                SyllableInfo {tag: Some(0), features: UNDEF_SYL} => [
-                    SegmentInfo {tag: Some(0), features: VOWEL_SEG},
+                SyllableInfo::new_tagged(0, UNDEF_SYL) => [
+                    SegmentInfo::new_tagged(0, VOWEL_SEG),
                 ],
             ],
         ],
@@ -377,9 +382,9 @@ fn test_rule_syllable_border_1() {
     );
     let replace_tree = d3tree![
         () => [
-            SyllableInfo {tag: Some(0), features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(0), features: UNDEF_SEG},
-                SegmentInfo {tag: None, features: T_SEG},
+            SyllableInfo::new_tagged(0, UNDEF_SYL) => [
+                SegmentInfo::new_tagged(0, UNDEF_SEG),
+                SegmentInfo::new_untagged(T_SEG),
             ]
         ]
     ];
@@ -422,8 +427,8 @@ fn test_rule_syllable_border_2() {
     let pattern = PhonoStringPattern::new(
         d3tree![
             () => [
-                SyllableInfo {tag: Some(0), features: UNDEF_SYL} => [
-                    SegmentInfo {tag: Some(0), features: VOWEL_SEG},
+                SyllableInfo::new_tagged(0, UNDEF_SYL) => [
+                    SegmentInfo::new_tagged(0, VOWEL_SEG),
                 ],
             ],
         ],
@@ -432,9 +437,9 @@ fn test_rule_syllable_border_2() {
     );
     let replace_tree = d3tree![
         () => [
-            SyllableInfo {tag: Some(0), features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(0), features: UNDEF_SEG},
-                SegmentInfo {tag: None, features: T_SEG},
+            SyllableInfo::new_tagged(0, UNDEF_SYL) => [
+                SegmentInfo::new_tagged(0, UNDEF_SEG),
+                SegmentInfo::new_untagged(T_SEG),
             ]
         ]
     ];
@@ -472,22 +477,22 @@ fn test_invalid_rule_double_id() {
     // V.V => VtV
     let match_tree = d3tree![
         () => [
-            SyllableInfo {tag: Some(0), features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(0), features: VOWEL_SEG},
+            SyllableInfo::new_tagged(0, UNDEF_SYL) => [
+                SegmentInfo::new_tagged(0, VOWEL_SEG),
             ],
         ],
         () => [
-            SyllableInfo {tag: Some(1), features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(1), features: VOWEL_SEG},
+            SyllableInfo::new_tagged(1, UNDEF_SYL) => [
+                SegmentInfo::new_tagged(1, VOWEL_SEG),
             ]
         ]
     ];
     let replace_tree = d3tree![
         () => [
-            SyllableInfo {tag: Some(0), features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(0), features: UNDEF_SEG},
-                SegmentInfo {tag: None, features: T_SEG},
-                SegmentInfo {tag: Some(0), features: UNDEF_SEG},
+            SyllableInfo::new_tagged(0, UNDEF_SYL) => [
+                SegmentInfo::new_tagged(0, UNDEF_SEG),
+                SegmentInfo::new_untagged(T_SEG),
+                SegmentInfo::new_tagged(0, UNDEF_SEG),
             ]
         ]
     ];
@@ -505,22 +510,22 @@ fn test_invalid_rule_undef_id() {
     // V.V => VtV
     let match_tree = d3tree![
         () => [
-            SyllableInfo {tag: Some(0), features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(0), features: VOWEL_SEG},
+            SyllableInfo::new_tagged(0, UNDEF_SYL) => [
+                SegmentInfo::new_tagged(0, VOWEL_SEG),
             ],
         ],
         () => [
-            SyllableInfo {tag: Some(1), features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(1), features: VOWEL_SEG},
+            SyllableInfo::new_tagged(1, UNDEF_SYL) => [
+                SegmentInfo::new_tagged(1, VOWEL_SEG),
             ]
         ]
     ];
     let replace_tree = d3tree![
         () => [
-            SyllableInfo {tag: Some(0), features: UNDEF_SYL} => [
-                SegmentInfo {tag: Some(1), features: UNDEF_SEG},
-                SegmentInfo {tag: None, features: T_SEG},
-                SegmentInfo {tag: Some(2), features: UNDEF_SEG},
+            SyllableInfo::new_tagged(0, UNDEF_SYL) => [
+                SegmentInfo::new_tagged(1, UNDEF_SEG),
+                SegmentInfo::new_untagged(T_SEG),
+                SegmentInfo::new_tagged(2, UNDEF_SEG),
             ]
         ]
     ];
@@ -544,7 +549,7 @@ macro_rules! gen_test_rule_apply {
 
                 let (_, string) = PhonoString::parse($input).unwrap();
                 let (_, expected) = PhonoString::parse($expected).unwrap();
-                let actual = rule_set.apply(string);
+                let actual = rule_set.apply(string).unwrap();
                 if actual != expected {
                     panic!("expected=[{expected}] != actual=[{actual}]");
                 }
@@ -597,3 +602,620 @@ gen_test_rule_apply!(across_word, "t -> d / V#_V", "a#ta", "a#da");
 gen_test_rule_apply!(shwa_removal_and_arrow, "ə → ∅ / _#", "taɪmə", "taɪm");
 gen_test_rule_apply!(multiple, "θ ð → t d", "ði.θo", "di.to");
 gen_test_rule_apply!(twin_tags, "V_0ʔV_0 -> Vː_0", "aʔaʔi", "aːʔi");
+gen_test_rule_apply!(empty_output, "ə -> / _#", "taɪmə", "taɪm");
+gen_test_rule_apply!(
+    multi_line_in_order,
+    "# lenition, in two steps\n\nt -> d / V_V\nd -> ð / V_V # voiced stops spirantize\n",
+    "ata",
+    "aða"
+);
+
+#[test]
+fn test_rule_set_error_line_number() {
+    let rules = "t -> d / V_V\n\n# bad rule below\nt -> -> d\n";
+    let err = PhonoRuleSet::parse(rules, PhonoRuleParseOpts::default())
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("line 4"), "{err}");
+}
+
+#[test]
+fn test_rule_set_error_span() {
+    let rules = "t -> d / V_V\n  t -> -> d # comment\n";
+    let err = PhonoRuleSet::parse(rules, PhonoRuleParseOpts::default())
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::RuleSyntax(_)), "{err}");
+
+    let span = err.span().unwrap();
+    assert_eq!((span.line, span.column), (2, 8));
+    assert_eq!(&rules[span.range.clone()], "-> d");
+    assert_eq!(
+        span.caret_diagram(),
+        "  |\n2 |   t -> -> d # comment\n  |        ^^^^"
+    );
+}
+
+#[test]
+fn test_rule_set_segment_error_span() {
+    let rules = "t -> d / V_V\na -> e / {i,˥}_\n";
+    let err = PhonoRuleSet::parse(rules, PhonoRuleParseOpts::default())
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::Segment(_)), "{err}");
+
+    let span = err.span().unwrap();
+    assert_eq!((span.line, span.column), (2, 13));
+    assert_eq!(&rules[span.range.clone()], "˥");
+}
+
+#[test]
+fn test_rule_tag_error() {
+    let err = PhonoRuleSet::parse("V_1 -> V_2", PhonoRuleParseOpts::default())
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::Tag(_)), "{err}");
+    assert_eq!(err.span().unwrap().range, 0..10);
+}
+
+#[test]
+fn test_rule_set_example_file() {
+    let rules = include_str!("../../../examples/rules.txt");
+    let rule_set = PhonoRuleSet::parse(rules, PhonoRuleParseOpts::default()).unwrap();
+    assert!(!rule_set.rules.is_empty());
+}
+gen_test_rule_apply!(
+    alpha_assimilation,
+    "s -> s[αvoi] / _C[αvoi]",
+    "asda#asta",
+    "azda#asta"
+);
+gen_test_rule_apply!(
+    alpha_nasal_assimilation,
+    "C[+nasal] -> C[αvoi] / _C[αvoi]",
+    "anta#anda",
+    "an[-voi]ta#anda"
+);
+gen_test_rule_apply!(
+    alpha_dissimilation,
+    "C -> C[-αvoi] / _C[αvoi]",
+    "abda",
+    "apda"
+);
+gen_test_rule_apply!(alpha_ascii, "C -> C[-@avoi] / _C[@avoi]", "abda", "apda");
+gen_test_rule_apply!(
+    alpha_agreement,
+    "V[αback] -> e / _CV[αback]",
+    "oku#aku",
+    "eku#aku"
+);
+
+#[test]
+fn test_rule_unbound_variable() {
+    let rule_set = PhonoRuleSet::parse("s -> s[αvoi] / _C", PhonoRuleParseOpts::default());
+    assert!(rule_set.is_err());
+}
+gen_test_rule_apply!(
+    bare_matrix_output,
+    "V -> [+nasal] / _C[+nasal]",
+    "an#ta",
+    "a\u{303}n#ta"
+);
+gen_test_rule_apply!(
+    bare_matrix_input,
+    "[+cons-son] -> [-voi] / _#",
+    "ab#ad",
+    "ap#at"
+);
+gen_test_rule_apply!(bare_matrix_context, "t -> d / [+syl]_[+syl]", "ata", "ada");
+gen_test_rule_apply!(bare_matrix_on_complete, "t -> [+voi] / V_", "ata", "ada");
+gen_test_rule_apply!(epenthesis, "∅ -> ə / C_C", "tka", "təka");
+gen_test_rule_apply!(prothesis, "∅ -> e / #_sk", "sko.la", "esko.la");
+gen_test_rule_apply!(prothesis_word_initial, "∅ -> ʔ / #_", "a#ta", "ʔa#ʔta");
+gen_test_rule_apply!(paragoge, "∅ -> ə / C_#", "tak#ta", "takə#ta");
+gen_test_rule_apply!(insert_two, "∅ -> əʔ / a_a", "aa", "aəʔa");
+
+#[test]
+fn test_rule_insertion_incomplete_segment() {
+    let rules = "∅ -> ə[+nasal] / a_n\n∅ -> ə[+nasal][+nasal] / a_n";
+    let err = PhonoRuleSet::parse(rules, PhonoRuleParseOpts::default())
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::RuleSyntax(_)), "{err}");
+
+    let span = err.span().unwrap();
+    assert_eq!((span.line, span.column), (2, 15));
+    assert_eq!(&rules[span.range.clone()], "[+nasal]");
+}
+
+gen_test_rule_apply!(delete_syllable, "e -> / _#", "ta.ko.e", "ta.ko");
+gen_test_rule_apply!(delete_word, "r -> ∅ / #_#", "ta#r#ko", "ta#ko");
+gen_test_rule_apply!(delete_stressed_syllable, "e -> / _#", "ta.ko'e", "ta.ko");
+
+#[test]
+fn test_rule_deletion_keeps_stress() {
+    let opts = PhonoRuleParseOpts {
+        empty_syllables: EmptySyllablePolicy::ToPrevious,
+        ..Default::default()
+    };
+    let rule_set = PhonoRuleSet::parse("e -> / _#", opts).unwrap();
+
+    let (_, string) = PhonoString::parse("ta.ko'e").unwrap();
+    let (_, expected) = PhonoString::parse("ta'ko").unwrap();
+    let actual = rule_set.apply(string).unwrap();
+    assert_eq!(actual, expected);
+    assert!(actual.tree.test_invariants());
+    assert!(actual.tree.are_leaves_depth_5());
+
+    // a secondary stress stays secondary, and doesn't weaken a primary stress
+    for (input, expected) in [("ta.ˌe", "ˌta"), ("ˈta.ˌe", "ˈta")] {
+        let (_, string) = PhonoString::parse(input).unwrap();
+        let (_, expected) = PhonoString::parse(expected).unwrap();
+        assert_eq!(rule_set.apply(string).unwrap(), expected);
+    }
+}
+
+gen_test_rule_apply!(primary_stress, "ˈta -> da", "ˈta.ˌta.ta", "ˈda.ˌta.ta");
+gen_test_rule_apply!(secondary_stress, "ˌta -> da", "ˈta.ˌta.ta", "ˈta.ˌda.ta");
+gen_test_rule_apply!(ascii_stress, "'ta -> da", "ta,ta'ta", "taˌtaˈda");
+gen_test_rule_apply!(any_stress, "ta -> da", "ˈta.ˌta.ta", "ˈda.ˌda.da");
+gen_test_rule_apply!(
+    stress_in_context,
+    "a -> ə / ˌC_",
+    "ˌta.ka.ˈpa",
+    "ˌtə.ka.ˈpa"
+);
+gen_test_rule_apply!(
+    foot_boundary,
+    "a -> e / _|",
+    "ˈpa.ta|ˈpa.ta",
+    "ˈpa.te|ˈpa.ta"
+);
+// a syllable boundary may be a foot boundary, but not a word boundary
+gen_test_rule_apply!(syllable_across_feet, "t -> d / a._", "pa|ta#ta", "pa|da#ta");
+gen_test_rule_apply!(word_not_foot, "a -> e / _#", "pa|ta#pa", "pa|te#pe");
+gen_test_rule_apply!(phrase_boundary, "a -> e / _‖", "pa#ta‖pa#ta", "pa#te‖pa#te");
+gen_test_rule_apply!(
+    ascii_phrase_boundary,
+    "a -> e / _||",
+    "pa#ta‖pa",
+    "pa#te‖pe"
+);
+gen_test_rule_apply!(
+    foot_features,
+    "a -> e / Σ[+trochaic]p_",
+    "Σ[+trochaic]pa|Σ[-trochaic]pa",
+    "Σ[+trochaic]pe|Σ[-trochaic]pa"
+);
+gen_test_rule_apply!(
+    foot_features_change,
+    "pa -> Σ[-trochaic]pa",
+    "Σ[+trochaic]pa.ta|Σ[+trochaic]ta",
+    "Σ[-trochaic]pa.ta|Σ[+trochaic]ta"
+);
+gen_test_rule_apply!(coda, "s[+coda] -> h", "pas.ta.sa", "pah.ta.sa");
+gen_test_rule_apply!(not_onset, "t[-onset] -> ʔ", "at.ta#tat", "aʔ.ta#taʔ");
+gen_test_rule_apply!(
+    onset_cluster,
+    "C[+onset] -> ∅ / _C[+onset]",
+    "pla.as.ta",
+    "la.as.ta"
+);
+gen_test_rule_apply!(any_nucleus, "[+nucleus] -> ə / C_#", "ta.ka#pl̩", "ta.kə#pə");
+
+#[test]
+fn test_rule_position_in_output() {
+    let rule_set = PhonoRuleSet::parse("t -> s[+coda]", PhonoRuleParseOpts::default());
+    assert!(rule_set.is_err());
+}
+
+gen_test_rule_apply!(heavy, "σ[+heavy]a -> e", "ta.tan.tai", "ta.ten.tei");
+gen_test_rule_apply!(light, "aσ[-heavy] -> e", "ta.tan.taː", "te.tan.taː");
+gen_test_rule_apply!(
+    heavy_next_syllable,
+    "a -> e / _.σ[+heavy]",
+    "ta.tan.ta.ta",
+    "te.tan.ta.ta"
+);
+gen_test_rule_apply!(
+    exactly_heavy,
+    "σ[+heavy-superheavy]a -> e",
+    "tan.taːn",
+    "ten.taːn"
+);
+
+gen_test_rule_apply!(syllable_features, "σ[+stress]o -> ue", "ˈno.vo", "ˈnue.vo");
+gen_test_rule_apply!(
+    syllable_features_context,
+    "o -> ue / σ[+stress]_",
+    "ˈno.vo",
+    "ˈnue.vo"
+);
+gen_test_rule_apply!(
+    syllable_features_change,
+    "σ[+stress]a -> σ[-stress]e",
+    "ˈta.ka",
+    "σ[-stress]te.ka"
+);
+gen_test_rule_apply!(
+    whole_syllable,
+    "σ -> σ[+stress+primary] / _.σ#",
+    "ta.ta.ta#ka.ka",
+    "taˈta.ta#ˈka.ka"
+);
+gen_test_rule_apply!(
+    whole_syllable_unstress,
+    "σ[+stress] -> σ[-stress]",
+    "ˈta.ta.ˌta",
+    "σ[-stress]ta.ta.σ[-stress]ta"
+);
+gen_test_rule_apply!(
+    stress_shift,
+    "σ[+stress].σ -> σ[-stress].σ[+stress+primary]",
+    "ˈta.ka#ˈpa.ta.ka",
+    "σ[-stress]taˈka#σ[-stress]paˈta.ka"
+);
+gen_test_rule_apply!(
+    whole_syllable_heavy,
+    "σ[+heavy] -> σ[+stress+primary] / _.σ#",
+    "ta.tan.ta#ta.ta.ta",
+    "taˈtan.ta#ta.ta.ta"
+);
+gen_test_rule_apply!(
+    whole_syllable_deletion,
+    "σ -> ∅ / σ._#",
+    "ta.ka#pa",
+    "ta#pa"
+);
+gen_test_rule_apply!(syllable_merger, "$ -> ∅ / V_V", "ta.a#ka.i", "taa#kai");
+gen_test_rule_apply!(syllable_merger_stress, "$ -> ∅ / V_V", "ta.ˈa", "ˈtaa");
+gen_test_rule_apply!(
+    syllable_merger_stronger_stress,
+    "$ -> ∅ / V_V",
+    "ˌta.ˈa",
+    "ˈtaa"
+);
+gen_test_rule_apply!(
+    syllable_merger_weaker_stress,
+    "$ -> ∅ / V_V",
+    "ˈta.ˌa",
+    "ˈtaa"
+);
+gen_test_rule_apply!(cross_syllable_replacement, "a.k -> ə", "ta.ˈka", "ˈtəa");
+gen_test_rule_apply!(
+    syllable_merger_chain,
+    "iter: $ -> ∅ / V_V",
+    "ta.a.a",
+    "taaa"
+);
+gen_test_rule_apply!(syllable_merger_all, "$ -> ∅ / V_V", "ta.a.a", "taaa");
+gen_test_rule_apply!(
+    syllable_merger_left_to_right,
+    "ltr: $ -> ∅ / V_V",
+    "ta.a.a",
+    "taaa"
+);
+gen_test_rule_apply!(syllable_merger_short, "$ -> ∅", "a.i.u.e", "aiue");
+gen_test_rule_apply!(word_merger, "# -> ∅", "ta#ka#pa", "ta.ka.pa");
+gen_test_rule_apply!(word_merger_short, "# -> ∅", "a#i#u", "a.i.u");
+gen_test_rule_apply!(word_merger_context, "# -> ∅ / a_k", "ta#ka#pa", "ta.ka#pa");
+gen_test_rule_apply!(phrase_merger, "‖ -> ∅", "ta‖ka", "ta#ka");
+gen_test_rule_apply!(boundary_raising, "$ -> # / a_k", "ta.ka.pa", "ta#ka.pa");
+gen_test_rule_apply!(word_boundary_insertion, "∅ -> # / a_k", "takapa", "ta#kapa");
+gen_test_rule_apply!(long_vowel_split, "aː -> a.a", "ˈtaːk", "ˈta.ak");
+
+#[test]
+fn test_rule_weight_opts() {
+    let (_, string) = PhonoString::parse("tan.tai").unwrap();
+    let opts = PhonoRuleParseOpts {
+        weight: WeightOpts {
+            moraic_codas: false,
+        },
+        ..Default::default()
+    };
+    let rule_set = PhonoRuleSet::parse("σ[+heavy]a -> e", opts).unwrap();
+    let (_, expected) = PhonoString::parse("tan.tei").unwrap();
+    assert_eq!(rule_set.apply(string).unwrap(), expected);
+}
+
+#[test]
+fn test_rule_weight_in_output() {
+    let rule_set = PhonoRuleSet::parse("a -> σ[+heavy]e", PhonoRuleParseOpts::default());
+    assert!(rule_set.is_err());
+}
+
+#[test]
+fn test_rule_contradictory_weights() {
+    for (rule, weights) in [
+        ("σ[+heavy-heavy]a -> e", "σ[+heavy-heavy]"),
+        ("a -> e / σ[+light+superheavy]_", "σ[+light+superheavy]"),
+    ] {
+        let err = PhonoRuleSet::parse(rule, PhonoRuleParseOpts::default())
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::RuleSyntax(_)), "{err}");
+        let span = err.span().unwrap();
+        assert_eq!(&rule[span.range.clone()], weights);
+    }
+}
+
+gen_test_rule_apply!(tone_change, "a˥˩ -> a˧", "ma˥˩.ma˥˩", "ma˧.ma˧");
+gen_test_rule_apply!(tone_only_toned, "a˥˩ -> a˧", "mâ.ma˦", "mā.ma˦");
+gen_test_rule_apply!(tone_diacritic, "á -> à", "má.ta", "mà.ta");
+// Mandarin third tone sandhi
+gen_test_rule_apply!(
+    tone_sandhi,
+    "V˨˩˦ -> V˧˥ / _.CV˨˩˦",
+    "ni˨˩˦.hau˨˩˦",
+    "ni˧˥.hau˨˩˦"
+);
+gen_test_rule_apply!(
+    tone_sandhi_no_match,
+    "V˨˩˦ -> V˧˥ / _.CV˨˩˦",
+    "ni˨˩˦.ma˥",
+    "ni˨˩˦.ma˥"
+);
+gen_test_rule_apply!(tone_kept, "t -> d / V_", "ma˥t", "ma˥d");
+
+gen_test_rule_apply!(simultaneous, "a -> b / a_", "aaa", "abb");
+gen_test_rule_apply!(simultaneous_prefix, "sim: a -> b / _a", "aaa", "bba");
+gen_test_rule_apply!(simultaneous_overlap, "aa -> b", "aaa", "ba");
+gen_test_rule_apply!(left_to_right, "ltr: a -> b / a_", "aaa", "aba");
+gen_test_rule_apply!(left_to_right_feeding, "ltr: a -> b / _a", "aaa", "bba");
+gen_test_rule_apply!(left_to_right_longer_output, "ltr: a -> aa", "ta", "taa");
+gen_test_rule_apply!(right_to_left, "rtl: a -> b / _a", "aaa", "aba");
+gen_test_rule_apply!(right_to_left_feeding, "rtl: a -> b / a_", "aaa", "abb");
+gen_test_rule_apply!(right_to_left_insertion, "rtl: ∅ -> ə / C_C", "tkt", "təkət");
+gen_test_rule_apply!(iterative, "iter: a -> b / b_", "baaa", "bbbb");
+gen_test_rule_apply!(iterative_cycle, "iter: C[αvoi] -> [-αvoi]", "ta", "da");
+gen_test_rule_apply!(xsampa_quoted, "\"tS\" -> \"dZ_w\" / V_V", "at͡ʃa", "ad͡ʒʷa");
+gen_test_rule_apply!(xsampa_quoted_context, "k -> x / \"{\"_", "\"{\"ka", "æxa");
+
+#[test]
+fn test_rule_iterative_unbounded() {
+    let apply = |rule: &str, input: &str| {
+        let rule_set = PhonoRuleSet::parse(rule, PhonoRuleParseOpts::default()).unwrap();
+        let (_, string) = PhonoString::parse(input).unwrap();
+        rule_set.apply(string).unwrap_err().to_string()
+    };
+
+    // the string doubles on every pass
+    let err = apply("iter: a -> aa", "a");
+    assert!(err.contains("past 16 segments"), "{err}");
+    let err = apply("iter: ∅ -> a", "a");
+    assert!(err.contains("past 16 segments"), "{err}");
+
+    // one more segment on every pass, which stays within the growth bound
+    let err = apply("iter: ∅ -> a / _#", "tatatatatatatatatata");
+    assert!(err.contains("after 100 passes"), "{err}");
+}
+
+#[test]
+fn test_rule_default_mode() {
+    let opts = PhonoRuleParseOpts {
+        mode: ApplicationMode::LeftToRight,
+        ..Default::default()
+    };
+    let rule_set = PhonoRuleSet::parse("a -> b / a_\nsim: b -> c / a_", opts).unwrap();
+    assert_eq!(rule_set.rules[0].mode, ApplicationMode::LeftToRight);
+    assert_eq!(rule_set.rules[1].mode, ApplicationMode::Simultaneous);
+
+    let (_, string) = PhonoString::parse("aaaa").unwrap();
+    let (_, expected) = PhonoString::parse("acac").unwrap();
+    assert_eq!(rule_set.apply(string).unwrap(), expected);
+}
+
+#[test]
+fn test_rule_set_derivation() {
+    let opts = PhonoRuleParseOpts::default();
+    let rule_set =
+        PhonoRuleSet::parse("t -> d / V_V\n\n# no match\nk -> g\ne -> / _#", opts).unwrap();
+
+    let (_, string) = PhonoString::parse("ate").unwrap();
+    let derivation = rule_set.derive(string.clone()).unwrap();
+    assert_eq!(derivation.steps.len(), 3);
+
+    let step = &derivation.steps[0];
+    assert_eq!(step.rule_idx, 0);
+    assert_eq!(step.source.as_ref().unwrap().line, 1);
+    assert_eq!(step.source.as_ref().unwrap().text, "t -> d / V_V");
+    assert_eq!(step.matches.len(), 1);
+    assert_eq!(step.matches[0].range, 1..2);
+    assert_eq!(step.output.format(), "ade");
+
+    assert!(derivation.steps[1].matches.is_empty());
+    assert_eq!(derivation.steps[2].source.as_ref().unwrap().line, 5);
+    assert_eq!(derivation.steps[2].output.format(), "ad");
+
+    assert_eq!(*derivation.output(), rule_set.apply(string).unwrap());
+    let table = derivation.format_table();
+    assert_eq!(
+        table.lines().count(),
+        3,
+        "unchanged steps aren't in the table"
+    );
+    assert!(table.contains("t -> d / V_V"));
+}
+
+#[test]
+fn test_rule_set_stress_step() {
+    let rules = "stress: latin\na -> e / ˈt_";
+    let rule_set = PhonoRuleSet::parse(rules, PhonoRuleParseOpts::default()).unwrap();
+    assert_eq!(rule_set.stress_steps[0].before_rule, 0);
+
+    let output = rule_set
+        .apply("ta.ta.ta#ta.tan.ta".parse().unwrap())
+        .unwrap();
+    assert_eq!(output.format(), "ˈte.ta.ta#taˈten.ta");
+
+    // a stress step is a step of the derivation, with a match per newly stressed syllable
+    let derivation = rule_set
+        .derive("ta.ta.ta#ta.tan.ta".parse().unwrap())
+        .unwrap();
+    assert_eq!(derivation.steps[0].matches.len(), 2);
+    let derivation = rule_set.derive("ta.ta".parse().unwrap()).unwrap();
+    assert_eq!(derivation.steps.len(), 2);
+    assert_eq!(derivation.steps[0].matches.len(), 1);
+    assert_eq!(derivation.steps[0].output.format(), "ˈta.ta");
+    assert!(derivation.format_table().contains("stress: latin"));
+
+    let err = PhonoRuleSet::parse("stress: dactylic", PhonoRuleParseOpts::default());
+    assert!(err.is_err());
+}
+
+/// apply `rules` with the resyllabify option `resyllabify`
+fn apply_resyllabified(rules: &str, resyllabify: Resyllabify, input: &str) -> PhonoString {
+    let opts = PhonoRuleParseOpts {
+        resyllabify,
+        ..Default::default()
+    };
+    let rule_set = PhonoRuleSet::parse(rules, opts).unwrap();
+    let output = rule_set.apply(input.parse().unwrap()).unwrap();
+    assert!(output.tree.test_invariants());
+    assert!(output.tree.are_leaves_depth_5());
+    output
+}
+
+#[test]
+fn test_rule_resyllabify_lost_nucleus() {
+    let rules = "a -> ∅ / _#";
+    let expected: PhonoString = "'ka.t".parse().unwrap();
+    assert_eq!(
+        apply_resyllabified(rules, Resyllabify::Never, "'ka.ta"),
+        expected
+    );
+
+    let expected: PhonoString = "'kat".parse().unwrap();
+    assert_eq!(
+        apply_resyllabified(rules, Resyllabify::AfterEachRule, "'ka.ta"),
+        expected
+    );
+}
+
+#[test]
+fn test_rule_resyllabify_at_end_derivation() {
+    let opts = PhonoRuleParseOpts {
+        resyllabify: Resyllabify::AtEnd,
+        ..Default::default()
+    };
+    let string: PhonoString = "'ka.ta".parse().unwrap();
+
+    // the rule step keeps the output of the rule, and the syllables are rebuilt in a last step
+    let rule_set = PhonoRuleSet::parse("a -> ∅ / _#", opts.clone()).unwrap();
+    let derivation = rule_set.derive(string.clone()).unwrap();
+    assert_eq!(derivation.steps.len(), 2);
+    assert_eq!(derivation.steps[0].output.format(), "ˈka.t");
+    assert_eq!(derivation.steps[0].matches[0].range, 3..4);
+    let last = &derivation.steps[1];
+    assert!(last.resyllabification);
+    assert_eq!(last.matches.len(), 1);
+    assert_eq!(last.matches[0].range, 0..3);
+    assert_eq!(*derivation.output(), rule_set.apply(string).unwrap());
+    assert!(derivation.format_table().contains("resyllabify"));
+
+    // without rules, like `apply`
+    let rule_set = PhonoRuleSet::parse("", opts).unwrap();
+    let string: PhonoString = "kat.a".parse().unwrap();
+    let derivation = rule_set.derive(string.clone()).unwrap();
+    assert_eq!(derivation.output().format(), "ka.ta");
+    assert_eq!(*derivation.output(), rule_set.apply(string).unwrap());
+}
+
+#[test]
+fn test_rule_resyllabify_keeps_stress() {
+    let expected: PhonoString = "ə.ka'ta".parse().unwrap();
+    let actual = apply_resyllabified("∅ -> ə / #_", Resyllabify::AtEnd, "ka'ta");
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn test_rule_resyllabify_at_end() {
+    // the second rule only applies if the syllables aren't rebuilt in between
+    let rules = "a -> ∅ / _#\nt -> d / $_";
+    let expected: PhonoString = "kat".parse().unwrap();
+    assert_eq!(
+        apply_resyllabified(rules, Resyllabify::AfterEachRule, "ka.ta"),
+        expected
+    );
+
+    let expected: PhonoString = "kad".parse().unwrap();
+    assert_eq!(
+        apply_resyllabified(rules, Resyllabify::AtEnd, "ka.ta"),
+        expected
+    );
+}
+gen_test_rule_apply!(
+    class_list,
+    "P = {p,t,k}\nP -> [+voi] / V_V",
+    "apa#asa#aka",
+    "aba#asa#aɡa"
+);
+gen_test_rule_apply!(
+    class_list_context,
+    "P = {p, t, k}\na -> ə / _P",
+    "ap#as",
+    "əp#as"
+);
+gen_test_rule_apply!(
+    class_multi_char,
+    "Nas = [+nasal]\nV -> [+nasal] / _Nas",
+    "an#as",
+    "a\u{303}n#as"
+);
+gen_test_rule_apply!(class_redefined, "S = {s}\nS -> h / V_", "aʃ#as", "aʃ#ah");
+
+#[test]
+fn test_rule_opts_classes() {
+    let mut opts = PhonoRuleParseOpts::default();
+    let liquids = NaturalClass::parse("{l, r}").unwrap();
+    opts.classes.define("Liq", liquids).unwrap();
+    let rule_set = PhonoRuleSet::parse("V -> ∅ / C_Liq", opts).unwrap();
+
+    let (_, string) = PhonoString::parse("pala#pata").unwrap();
+    let (_, expected) = PhonoString::parse("pla#pata").unwrap();
+    assert_eq!(rule_set.apply(string).unwrap(), expected);
+}
+
+#[test]
+fn test_rule_class_definition_error() {
+    let rules = "P = {p,%}\nP -> b";
+    let err = PhonoRuleSet::parse(rules, PhonoRuleParseOpts::default())
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::Segment(_)), "{err}");
+    assert_eq!(&rules[err.span().unwrap().range.clone()], "{p,%}");
+}
+
+#[test]
+fn test_rule_geometry_check() {
+    let rules = "t -> [+round]\ni -> [+low]";
+    let (_, string) = PhonoString::parse("ti").unwrap();
+
+    let rule_set = PhonoRuleSet::parse(rules, PhonoRuleParseOpts::default()).unwrap();
+    assert!(rule_set.apply(string.clone()).is_ok());
+
+    let opts = PhonoRuleParseOpts {
+        geometry: GeometryCheck::Validate,
+        ..Default::default()
+    };
+    let rule_set = PhonoRuleSet::parse(rules, opts).unwrap();
+    let err = rule_set.apply(string.clone()).unwrap_err();
+    assert!(err.to_string().contains("[-lab+round]"), "{err}");
+
+    // the rounding of the non-labial t is dropped
+    let opts = PhonoRuleParseOpts {
+        geometry: GeometryCheck::Normalize,
+        ..Default::default()
+    };
+    let rule_set = PhonoRuleSet::parse("t -> [+round]", opts.clone()).unwrap();
+    let actual = rule_set.apply(string.clone()).unwrap();
+    let (_, rounded) = parse_segment("t[+round]").unwrap();
+    assert_eq!(actual.tree.layer_4()[0].0, rounded.normalized());
+    assert_eq!(actual.tree.layer_4()[1].0, string.tree.layer_4()[1].0);
+    assert_eq!(actual.to_string(), "ti");
+
+    // the segments the rules didn't contradict keep their features, so later rules match them
+    let rule_set = PhonoRuleSet::parse("t -> d\na -> e", opts).unwrap();
+    let (_, string) = PhonoString::parse("ta").unwrap();
+    assert_eq!(rule_set.apply(string).unwrap().to_string(), "de");
+}
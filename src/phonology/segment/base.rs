@@ -8,8 +8,9 @@ use crate::phonology::{
     feature::{
         Feature,
         FeatureState::{self, NEG, POS, UNDEF},
+        VariableBindings,
     },
-    segment::{SEG_FEATURE_COUNT, format_segment},
+    segment::{CONS, CONT, NASAL, SEG_FEATURE_COUNT, SON, SYL, format_segment},
 };
 
 /// represents a set of phonological features
@@ -23,7 +24,7 @@ pub struct SegmentFeatures {
 
 impl SegmentFeatures {
     /// construct a segement from an array of features
-    /// syl,long,cons,son,cont,delrel,approx,tap,trill,nasal,voi,spgl,congl,lab,round,labdent,cor,ant,dist,strident,lateral,dor,high,low,front,back,tense
+    /// syl,long,cons,son,cont,delrel,approx,tap,trill,nasal,voi,spgl,congl,lab,round,labdent,cor,ant,dist,strident,lateral,dor,high,low,front,back,tense,suction
     pub const fn from_features(features: [FeatureState; SEG_FEATURE_COUNT as usize]) -> Self {
         SegmentFeatures { features }
     }
@@ -53,10 +54,18 @@ impl SegmentFeatures {
     /// - if a feature is `POS` or `NEG` in `pattern`, it must be identical in this segment
     /// - if a features is `NA` in `pattern`, it can be `POS`, `NEG`, or `NA` in this segment
     ///
+    /// - if a feature is a variable (`α`, `-α`) in `pattern`, it must be `POS` or `NEG` in this
+    ///   segment. see `bind_variables` for the value of the variable.
+    ///
     /// otherwise, returns false.
     pub fn matches(&self, pattern: &SegmentFeatures) -> bool {
         for i in 0..(SEG_FEATURE_COUNT as usize) {
-            if pattern.features[i] == UNDEF || pattern.features[i] == self.features[i] {
+            let is_variable_match =
+                pattern.features[i].is_variable() && matches!(self.features[i], POS | NEG);
+            if pattern.features[i] == UNDEF
+                || pattern.features[i] == self.features[i]
+                || is_variable_match
+            {
                 continue;
             } else {
                 return false;
@@ -66,10 +75,63 @@ impl SegmentFeatures {
         true
     }
 
+    /// bind the variables in `pattern` to this segment's feature values.
+    /// returns false if a variable would be bound to two different values.
+    pub fn bind_variables(
+        &self,
+        pattern: &SegmentFeatures,
+        bindings: &mut VariableBindings,
+    ) -> bool {
+        pattern
+            .features
+            .iter()
+            .zip(self.features.iter())
+            .all(|(p, s)| p.bind(*s, bindings))
+    }
+
+    /// replace the variables in this feature set by their bound values.
+    /// returns None if a variable isn't bound.
+    pub fn substitute_variables(&self, bindings: &VariableBindings) -> Option<Self> {
+        let mut result = self.clone();
+        for feature in &mut result.features {
+            *feature = feature.substitute(bindings)?;
+        }
+        Some(result)
+    }
+
+    /// returns true if any feature is a variable
+    pub fn has_variables(&self) -> bool {
+        self.features.iter().any(|f| f.is_variable())
+    }
+
     pub fn features(&self) -> &[FeatureState; SEG_FEATURE_COUNT as usize] {
         &self.features
     }
 
+    /// Rank of the segment on the sonority scale, from its major class and manner features:
+    /// stops (1) < fricatives (2) < nasals (3) < liquids (4) < glides (5) < vowels (6).
+    /// Segments without major class features rank 0.
+    pub fn sonority(&self) -> u8 {
+        let is = |feature: u8| self.features[feature as usize] == POS;
+        let is_not = |feature: u8| self.features[feature as usize] == NEG;
+
+        if is(SYL) {
+            6
+        } else if is(SON) && is_not(CONS) {
+            5
+        } else if is(SON) && is(NASAL) {
+            3
+        } else if is(SON) {
+            4
+        } else if is_not(SON) && is(CONT) {
+            2
+        } else if is_not(SON) {
+            1
+        } else {
+            0
+        }
+    }
+
     // Number of POS/NEG features in `target` that differ from `base`: the cost of choosing `base`.
     pub fn diff_count(base: &Self, target: &Self) -> usize {
         base.features
@@ -5,7 +5,8 @@ use crate::phonology::feature::FeatureState::*;
 // syl,   	long,	cons,	son,	cont,	delrel,
 // approx,	tap,	trill,	nasal,	voi,	spgl,	congl,
 // lab,		round,	labdent,cor,	ant,	dist,	strident,
-// lateral,	dor,	high,	low,	front,	back,	tense
+// lateral,	dor,	high,	low,	front,	back,	tense,
+// suction
 
 // segments that represent common ipa symbols
 #[rustfmt::skip]
@@ -15,35 +16,35 @@ pub const DIACRITICS: &[(char, SegmentFeatures)] = &[
 		[UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
 		 UNDEF,UNDEF,UNDEF,POS,  UNDEF,UNDEF,UNDEF,
 		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
-		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
 	),
 	// triangular colon: long
 	('\u{02D0}', SegmentFeatures::from_features(
 		[UNDEF,POS,  UNDEF,UNDEF,UNDEF,UNDEF,
 		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
 		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
-		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
 	),
 	// combining down tack below: lowered
 	('\u{031E}', SegmentFeatures::from_features(
 		[UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
 		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
 		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
-		 UNDEF,UNDEF,UNDEF,POS,  UNDEF,UNDEF,UNDEF])
+		 UNDEF,UNDEF,UNDEF,POS,  UNDEF,UNDEF,UNDEF,UNDEF])
 	),
 	// modifier down tack: lowered
 	('\u{02D5}', SegmentFeatures::from_features(
 		[UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
 		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
 		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
-		 UNDEF,UNDEF,UNDEF,POS,  UNDEF,UNDEF,UNDEF])
+		 UNDEF,UNDEF,UNDEF,POS,  UNDEF,UNDEF,UNDEF,UNDEF])
 	),
 	// labialization
 	('\u{02B7}', SegmentFeatures::from_features(
 		[UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
 		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
 		 POS,  POS  ,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
-		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
 	),
 	// dental : COMBINING BRIDGE BELOW
 	// dental diacritic (t̪) indicates [+anterior, +distributed] within the coronal node [+cor]
@@ -51,7 +52,7 @@ pub const DIACRITICS: &[(char, SegmentFeatures)] = &[
 		[UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
 		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
 		 UNDEF,UNDEF,UNDEF,POS  ,POS  ,POS  ,UNDEF,
-		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
 	),
 	// ejective: ʼ
 	// features: -spgl, -son, -voi, +cons
@@ -59,7 +60,7 @@ pub const DIACRITICS: &[(char, SegmentFeatures)] = &[
 		[UNDEF,UNDEF,POS  ,NEG  ,UNDEF,UNDEF,
 		 UNDEF,UNDEF,UNDEF,UNDEF,NEG  ,NEG  ,UNDEF,
 		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
-		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF]
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF]
 	)),
 	// palatalization : ʲ (U+02B2. MODIFIER LETTER SMALL J)
 	// features: [+high+front-low-back]
@@ -67,14 +68,132 @@ pub const DIACRITICS: &[(char, SegmentFeatures)] = &[
 		[UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
 		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
 		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
-		 UNDEF,UNDEF,POS  ,NEG  ,POS  ,NEG  ,UNDEF]
+		 UNDEF,UNDEF,POS  ,NEG  ,POS  ,NEG  ,UNDEF,UNDEF]
 	)),
-	// length : ː (U+02D0, MODIFIER LETTER TRIANGULAR COLON)
-	// features: [+long]
-	('\u{02D0}', SegmentFeatures::from_features(
+	// combining ring below: voiceless
+	('\u{0325}', SegmentFeatures::from_features(
+		[UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,NEG  ,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
+	),
+	// combining ring above: voiceless, for symbols with a descender
+	('\u{030A}', SegmentFeatures::from_features(
+		[UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,NEG  ,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
+	),
+	// combining diaeresis below: breathy voice
+	('\u{0324}', SegmentFeatures::from_features(
+		[UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,POS  ,POS  ,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
+	),
+	// combining tilde below: creaky voice
+	('\u{0330}', SegmentFeatures::from_features(
+		[UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,POS  ,UNDEF,POS  ,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
+	),
+	// aspiration: ʰ
+	('\u{02B0}', SegmentFeatures::from_features(
+		[UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,POS  ,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
+	),
+	// combining vertical line below: syllabic
+	('\u{0329}', SegmentFeatures::from_features(
+		[POS  ,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
+	),
+	// combining vertical line above: syllabic, for symbols with a descender
+	('\u{030D}', SegmentFeatures::from_features(
+		[POS  ,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
+	),
+	// combining inverted breve below: non-syllabic
+	('\u{032F}', SegmentFeatures::from_features(
+		[NEG  ,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
+	),
+	// combining up tack below: raised
+	('\u{031D}', SegmentFeatures::from_features(
+		[UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,POS  ,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
+	),
+	// modifier up tack: raised
+	('\u{02D4}', SegmentFeatures::from_features(
+		[UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,POS  ,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
+	),
+	// combining plus sign below: advanced
+	('\u{031F}', SegmentFeatures::from_features(
+		[UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,POS  ,UNDEF,UNDEF,UNDEF])
+	),
+	// combining minus sign below: retracted
+	('\u{0320}', SegmentFeatures::from_features(
+		[UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,POS  ,UNDEF,UNDEF])
+	),
+	// combining left tack below: advanced tongue root
+	('\u{0318}', SegmentFeatures::from_features(
+		[UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,POS,UNDEF  ])
+	),
+	// combining right tack below: retracted tongue root
+	('\u{0319}', SegmentFeatures::from_features(
+		[UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,NEG,UNDEF  ])
+	),
+	// velarization: ˠ
+	('\u{02E0}', SegmentFeatures::from_features(
+		[UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,POS  ,POS  ,UNDEF,UNDEF,POS  ,UNDEF,UNDEF])
+	),
+	// pharyngealization: ˤ
+	('\u{02E4}', SegmentFeatures::from_features(
+		[UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,POS  ,UNDEF,POS  ,UNDEF,POS  ,UNDEF,UNDEF])
+	),
+	// half-long: ˑ. there is no half-long feature, it is parsed as long and formatted as ː
+	('\u{02D1}', SegmentFeatures::from_features(
 		[UNDEF,POS  ,UNDEF,UNDEF,UNDEF,UNDEF,
-		UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
-		UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
-		UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF]
-	)),
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
+	),
+	// rhoticity: ˞, like a retroflex
+	('\u{02DE}', SegmentFeatures::from_features(
+		[UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,
+		 UNDEF,UNDEF,UNDEF,POS  ,NEG  ,NEG  ,UNDEF,
+		 UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
+	),
 ];
@@ -34,11 +34,14 @@ pub const LOW: u8 = 23;
 pub const FRONT: u8 = 24;
 pub const BACK: u8 = 25;
 pub const TENSE: u8 = 26;
+// AIRSTREAM
+// ingressive airstream: implosives and clicks
+pub const SUCTION: u8 = 27;
 
-pub const SEG_FEATURE_COUNT: u8 = 27;
+pub const SEG_FEATURE_COUNT: u8 = 28;
 
 pub const SEG_FEATURE_NAMES: [&str; SEG_FEATURE_COUNT as usize] = [
     "syl", "long", "cons", "son", "cont", "delrel", "approx", "tap", "trill", "nasal", "voi",
     "spgl", "congl", "lab", "round", "labdent", "cor", "ant", "dist", "strident", "lateral", "dor",
-    "high", "low", "front", "back", "tense",
+    "high", "low", "front", "back", "tense", "suction",
 ];
@@ -1,52 +1,184 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock, Mutex},
+};
+
 use crate::phonology::{
-    feature::FeatureState,
-    segment::{DIACRITICS, IPA_BASES, NATURAL_CLASSES, SEG_FEATURE_NAMES, SegmentFeatures},
+    feature::{FeatureState, format_variable},
+    segment::{
+        Notation, SEG_FEATURE_NAMES, SegmentFeatures, SegmentTable, XSAMPA_BASES,
+        XSAMPA_DIACRITICS, first_ipa_diacritic, longest_ipa_base, nfd_prefix_len,
+    },
 };
 
 // Maximum number of diacritics to stack when searching for an IPA representation.
 // ɤ+ʲ has the same features as i; exact base matches are tried first to avoid ɤʲ for i.
-const MAX_DIACRITICS: usize = 1;
+const MAX_DIACRITICS: usize = 2;
+
+// The shortest symbol with diacritics for each segment reachable from a base of a table
+type DiacriticIndex = HashMap<SegmentFeatures, String>;
 
+// The last table used for formatting, with its index
+type IndexedTable = (Arc<SegmentTable>, Arc<DiacriticIndex>);
+
+// rebuilt when another table is installed
+static DIACRITIC_INDEX: LazyLock<Mutex<Option<IndexedTable>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Format a segment with the symbols of the current `SegmentTable`
 pub fn format_segment(segment: &SegmentFeatures) -> String {
-    format_ipa_exact(segment)
-        .or_else(|| format_ipa_diacritics(segment))
-        .or_else(|| format_natural_class_exact(segment))
-        .or_else(|| format_ipa_feature_list(segment))
-        .or_else(|| format_natural_class_feature_list(segment))
+    let table = SegmentTable::current();
+    format_ipa_exact(&table, segment)
+        .or_else(|| format_ipa_diacritics(&table, segment))
+        .or_else(|| format_natural_class_exact(&table, segment))
+        .or_else(|| format_ipa_feature_list(&table, segment))
+        .or_else(|| format_natural_class_feature_list(&table, segment))
         .unwrap_or_else(|| format_bare_feature_list(segment))
 }
 
-fn format_ipa_exact(segment: &SegmentFeatures) -> Option<String> {
-    IPA_BASES
-        .iter()
+/// like `format_segment`, with the symbols of `notation`
+pub fn format_segment_in(segment: &SegmentFeatures, notation: Notation) -> String {
+    match notation {
+        Notation::Ipa => format_segment(segment),
+        Notation::XSampa => format_segment_xsampa(segment),
+    }
+}
+
+/// like `format_segment_in`, with the symbols of `table` instead of the current table
+pub fn format_segment_with(
+    segment: &SegmentFeatures,
+    notation: Notation,
+    table: &Arc<SegmentTable>,
+) -> String {
+    table.scope(|| format_segment_in(segment, notation))
+}
+
+/// Format a segment in X-SAMPA. Natural class symbols are X-SAMPA symbols, so segments that
+/// aren't close to an IPA symbol are formatted as a feature list.
+pub fn format_segment_xsampa(segment: &SegmentFeatures) -> String {
+    let table = SegmentTable::current();
+    format_ipa_exact(&table, segment)
+        .or_else(|| format_ipa_diacritics(&table, segment))
+        .or_else(|| format_ipa_feature_list(&table, segment))
+        .map(|ipa| ipa_to_xsampa(&ipa))
+        .unwrap_or_else(|| format_bare_feature_list(segment))
+}
+
+fn format_ipa_exact(table: &SegmentTable, segment: &SegmentFeatures) -> Option<String> {
+    // a normalized segment (see `SegmentFeatures::normalize`) keeps the symbol of its base, the
+    // features under its negative nodes don't apply
+    let bases = || table.bases.iter();
+    bases()
         .find(|(_, seg)| seg == segment)
+        .or_else(|| {
+            if !segment.contradictions().is_empty() {
+                return None;
+            }
+            let reduced = segment.clone().without_negative_dependents();
+            bases().find(|(_, seg)| seg.clone().without_negative_dependents() == reduced)
+        })
         .map(|(sym, _)| sym.to_string())
 }
 
-fn format_ipa_diacritics(segment: &SegmentFeatures) -> Option<String> {
-    IPA_BASES.iter().find_map(|(sym, seg)| {
-        match_diacritics(seg.clone(), segment, MAX_DIACRITICS)
-            .map(|diacritics| format!("{}{}", sym, diacritics))
-    })
+fn format_ipa_diacritics(table: &Arc<SegmentTable>, segment: &SegmentFeatures) -> Option<String> {
+    diacritic_index(table).get(segment).cloned()
 }
 
-fn format_natural_class_exact(segment: &SegmentFeatures) -> Option<String> {
-    NATURAL_CLASSES
+fn diacritic_index(table: &Arc<SegmentTable>) -> Arc<DiacriticIndex> {
+    let mut cached = DIACRITIC_INDEX.lock().unwrap_or_else(|e| e.into_inner());
+    match &*cached {
+        Some((indexed, index)) if Arc::ptr_eq(indexed, table) => index.clone(),
+        _ => {
+            let index = Arc::new(build_diacritic_index(table));
+            *cached = Some((table.clone(), index.clone()));
+            index
+        }
+    }
+}
+
+// Breadth first: the segments with one diacritic, then two... so each segment gets the
+// shortest stack. Within a level, the first base and the first diacritics of the table win.
+// Combining diacritics are written before spacing ones, like n̩ː, and each symbol is indexed
+// by the features it is parsed to: the diacritics in written order, and no stack that starts
+// another base, like ŋ with ̠ which is read as ŋ̠.
+pub(super) fn build_diacritic_index(table: &SegmentTable) -> DiacriticIndex {
+    let mut index = DiacriticIndex::new();
+    let mut level: Vec<(&str, &SegmentFeatures, Vec<&str>, Vec<char>)> = vec![];
+    for (symbol, seg) in &table.bases {
+        let parsed = longest_ipa_base(table, symbol).map(|(_, parsed)| parsed);
+        if index.contains_key(seg) || parsed != Some(seg) {
+            continue;
+        }
+        index.insert(seg.clone(), symbol.clone());
+        let longer = table
+            .bases
+            .iter()
+            .map(|(other, _)| other.as_str())
+            .filter(|other| nfd_prefix_len(other, symbol).is_some_and(|end| end < other.len()))
+            .collect();
+        level.push((symbol, seg, longer, vec![]));
+    }
+
+    // what each diacritic is read as, the first of the table with its symbol
+    let parsed: HashMap<char, &SegmentFeatures> = table
+        .diacritics
         .iter()
-        .find(|(_, seg)| seg == segment)
+        .filter_map(|(d, _)| {
+            first_ipa_diacritic(table, d.encode_utf8(&mut [0; 4])).map(|(_, seg)| (*d, seg))
+        })
+        .collect();
+    for _ in 0..MAX_DIACRITICS {
+        let mut next_level = vec![];
+        for (base, base_seg, longer, diacritics) in &level {
+            for (d, _) in &table.diacritics {
+                let mut stack = diacritics.clone();
+                stack.push(*d);
+                stack.sort_by_key(|c| !is_combining(*c));
+                let symbol = format!("{base}{}", String::from_iter(&stack));
+                if longer.iter().any(|l| nfd_prefix_len(&symbol, l).is_some()) {
+                    continue;
+                }
+                let combined = stack
+                    .iter()
+                    .fold((*base_seg).clone(), |seg, c| seg + parsed[c].clone());
+                if index.contains_key(&combined) {
+                    continue;
+                }
+                index.insert(combined, symbol);
+                next_level.push((*base, *base_seg, longer.clone(), stack));
+            }
+        }
+        level = next_level;
+    }
+    index
+}
+
+fn is_combining(c: char) -> bool {
+    ('\u{0300}'..='\u{036F}').contains(&c)
+}
+
+fn format_natural_class_exact(table: &SegmentTable, segment: &SegmentFeatures) -> Option<String> {
+    table
+        .classes
+        .features()
+        .find(|(_, seg)| *seg == segment)
         .map(|(sym, _)| sym.to_string())
 }
 
-fn format_ipa_feature_list(segment: &SegmentFeatures) -> Option<String> {
-    IPA_BASES
+fn format_ipa_feature_list(table: &SegmentTable, segment: &SegmentFeatures) -> Option<String> {
+    table
+        .bases
         .iter()
         .min_by_key(|(_, seg)| SegmentFeatures::diff_count(seg, segment))
         .map(|(sym, base)| format!("{}[{}]", sym, diff_feature_list(base, segment)))
 }
 
-fn format_natural_class_feature_list(segment: &SegmentFeatures) -> Option<String> {
-    NATURAL_CLASSES
-        .iter()
+fn format_natural_class_feature_list(
+    table: &SegmentTable,
+    segment: &SegmentFeatures,
+) -> Option<String> {
+    table
+        .classes
+        .features()
         .min_by_key(|(_, seg)| SegmentFeatures::diff_count(seg, segment))
         .map(|(sym, base)| format!("{}[{}]", sym, diff_feature_list(base, segment)))
 }
@@ -54,34 +186,56 @@ fn format_natural_class_feature_list(segment: &SegmentFeatures) -> Option<String
 fn format_bare_feature_list(segment: &SegmentFeatures) -> String {
     let mut result = "[".to_string();
     for (i, feature) in SEG_FEATURE_NAMES.iter().enumerate() {
-        match segment.features[i] {
-            FeatureState::POS => result = result + "+" + feature,
-            FeatureState::NEG => result = result + "-" + feature,
-            _ => {}
+        if let Some(value) = format_feature_value(segment.features[i]) {
+            result = result + &value + feature;
         }
     }
     result + "]"
 }
 
-// Returns the diacritic string to append to a base to reach `target`, or None.
-fn match_diacritics(
-    seg: SegmentFeatures,
-    target: &SegmentFeatures,
-    depth: usize,
-) -> Option<String> {
-    if depth == 0 {
-        return None;
+/// The sign written before a feature name in a feature set. None for `UNDEF` and `NA`.
+pub fn format_feature_value(state: FeatureState) -> Option<String> {
+    match state {
+        FeatureState::POS => Some("+".to_string()),
+        FeatureState::NEG => Some("-".to_string()),
+        FeatureState::ALPHA(v) => Some(format_variable(v)),
+        FeatureState::NALPHA(v) => Some(format!("-{}", format_variable(v))),
+        FeatureState::UNDEF | FeatureState::NA => None,
     }
-    for (d, d_seg) in DIACRITICS {
-        let combined = seg.clone() + d_seg.clone();
-        if &combined == target {
-            return Some(d.to_string());
-        }
-        if let Some(suffix) = match_diacritics(combined, target, depth - 1) {
-            return Some(format!("{}{}", d, suffix));
+}
+
+// Transliterate IPA symbols and diacritics to X-SAMPA. Other characters, like feature lists,
+// are kept.
+fn ipa_to_xsampa(ipa: &str) -> String {
+    let symbols = XSAMPA_BASES
+        .iter()
+        .map(|(xsampa, ipa)| (*xsampa, ipa.to_string()));
+    let diacritics = XSAMPA_DIACRITICS
+        .iter()
+        .map(|(xsampa, ipa)| (*xsampa, ipa.to_string()));
+    let table: Vec<(&str, String)> = symbols.chain(diacritics).collect();
+
+    let mut result = String::new();
+    let mut rest = ipa;
+    while let Some(c) = rest.chars().next() {
+        // the longest symbol, so affricates aren't split. the first one wins ties.
+        let found = table
+            .iter()
+            .filter_map(|(xsampa, ipa)| nfd_prefix_len(rest, ipa).map(|end| (end, *xsampa)))
+            .rev()
+            .max_by_key(|(end, _)| *end);
+        match found {
+            Some((end, xsampa)) => {
+                result.push_str(xsampa);
+                rest = &rest[end..];
+            }
+            None => {
+                result.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
         }
     }
-    None
+    result
 }
 
 // Feature list string for features where `target` is POS/NEG and differs from `base`.
@@ -91,16 +245,9 @@ fn diff_feature_list(base: &SegmentFeatures, target: &SegmentFeatures) -> String
         if t == b {
             continue;
         }
-        match t {
-            FeatureState::POS => {
-                result.push('+');
-                result.push_str(SEG_FEATURE_NAMES[i]);
-            }
-            FeatureState::NEG => {
-                result.push('-');
-                result.push_str(SEG_FEATURE_NAMES[i]);
-            }
-            _ => {}
+        if let Some(value) = format_feature_value(*t) {
+            result.push_str(&value);
+            result.push_str(SEG_FEATURE_NAMES[i]);
         }
     }
     result
@@ -0,0 +1,120 @@
+use std::fmt;
+
+use crate::error::*;
+use crate::phonology::{
+    feature::{Feature, FeatureState},
+    segment::{
+        ANT, BACK, COR, DIST, DOR, FRONT, HIGH, LAB, LABDENT, LOW, ROUND, SEG_FEATURE_NAMES,
+        SegmentFeatures,
+    },
+};
+
+/// The place nodes of the feature geometry, with the features they dominate. A dependent
+/// feature only applies when its node is positive: `[-lab]` segments aren't rounded.
+pub const FEATURE_GEOMETRY: &[(Feature, &[Feature])] = &[
+    (LAB, &[ROUND, LABDENT]),
+    (COR, &[ANT, DIST]),
+    (DOR, &[HIGH, LOW, FRONT, BACK]),
+];
+
+/// Pairs of features that can't both be positive
+pub const EXCLUSIVE_FEATURES: &[(Feature, Feature)] = &[(HIGH, LOW)];
+
+/// A combination of feature values the feature geometry doesn't allow
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Contradiction {
+    /// a positive dependent under a negative node, like `[-lab+round]`
+    Dependent { node: Feature, dependent: Feature },
+    /// two positive exclusive features, like `[+high+low]`
+    Exclusive(Feature, Feature),
+}
+
+impl fmt::Display for Contradiction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Contradiction::Dependent { node, dependent } => write!(
+                f,
+                "[-{}+{}]",
+                SEG_FEATURE_NAMES[*node as usize], SEG_FEATURE_NAMES[*dependent as usize]
+            ),
+            Contradiction::Exclusive(a, b) => write!(
+                f,
+                "[+{}+{}]",
+                SEG_FEATURE_NAMES[*a as usize], SEG_FEATURE_NAMES[*b as usize]
+            ),
+        }
+    }
+}
+
+impl SegmentFeatures {
+    /// The contradictions of this segment with the feature geometry (see `FEATURE_GEOMETRY` and
+    /// `EXCLUSIVE_FEATURES`)
+    pub fn contradictions(&self) -> Vec<Contradiction> {
+        let is = |feature: Feature, state: FeatureState| self.features[feature as usize] == state;
+
+        let dependents = FEATURE_GEOMETRY.iter().flat_map(|(node, dependents)| {
+            dependents
+                .iter()
+                .filter(|dependent| {
+                    is(*node, FeatureState::NEG) && is(**dependent, FeatureState::POS)
+                })
+                .map(|dependent| Contradiction::Dependent {
+                    node: *node,
+                    dependent: *dependent,
+                })
+        });
+        let exclusive = EXCLUSIVE_FEATURES
+            .iter()
+            .filter(|(a, b)| is(*a, FeatureState::POS) && is(*b, FeatureState::POS))
+            .map(|(a, b)| Contradiction::Exclusive(*a, *b));
+
+        dependents.chain(exclusive).collect()
+    }
+
+    /// Fails with the contradictions of this segment with the feature geometry, if any
+    pub fn validate(&self) -> Result<()> {
+        let contradictions = self.contradictions();
+        if contradictions.is_empty() {
+            return Ok(());
+        }
+        let list: Vec<String> = contradictions.iter().map(|c| c.to_string()).collect();
+        Err(Error::segment(
+            format!(
+                "[{self}] contradicts the feature geometry: {}",
+                list.join(", ")
+            ),
+            None,
+        ))
+    }
+
+    /// Set the positive features dominated by a negative node to `NA`, like `round` in
+    /// `[-lab+round]`. The other features of the segment are kept, so it still matches the
+    /// patterns it matched before.
+    pub fn normalize(&mut self) {
+        for contradiction in self.contradictions() {
+            if let Contradiction::Dependent { dependent, .. } = contradiction {
+                self.features[dependent as usize] = FeatureState::NA;
+            }
+        }
+    }
+
+    /// The segment with all the features dominated by a negative node set to `NA`. Segments
+    /// that only differ by features that don't apply are equal once reduced.
+    pub(crate) fn without_negative_dependents(mut self) -> Self {
+        for (node, dependents) in FEATURE_GEOMETRY {
+            if self.features[*node as usize] != FeatureState::NEG {
+                continue;
+            }
+            for dependent in *dependents {
+                self.features[*dependent as usize] = FeatureState::NA;
+            }
+        }
+        self
+    }
+
+    /// like `normalize`, returns the normalized segment
+    pub fn normalized(mut self) -> Self {
+        self.normalize();
+        self
+    }
+}
@@ -2,17 +2,25 @@ mod base;
 mod diacritics;
 mod feature;
 mod format;
+mod geometry;
 mod ipa;
 mod natural_classes;
+mod notation;
 mod parse;
+mod table;
+mod xsampa;
 
 pub use base::*;
 pub use diacritics::*;
 pub use feature::*;
 pub use format::*;
+pub use geometry::*;
 pub use ipa::*;
 pub use natural_classes::*;
+pub use notation::*;
 pub use parse::*;
+pub use table::*;
+pub use xsampa::*;
 
 #[cfg(test)]
 mod test;
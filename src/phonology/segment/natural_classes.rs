@@ -1,25 +1,142 @@
+use crate::error::*;
 use crate::phonology::feature::FeatureState::{NEG, POS, UNDEF};
-use crate::phonology::segment::SegmentFeatures;
+use crate::phonology::segment::{SegmentFeatures, parse_segment};
 
-// syl,long,cons,son,cont,delrel,approx,tap,trill,nasal,voi,spgl,congl,lab,round,labdent,cor,ant,dist,strident,lateral,dor,high,low,front,back,tense
+// syl,long,cons,son,cont,delrel,approx,tap,trill,nasal,voi,spgl,congl,lab,round,labdent,cor,ant,dist,strident,lateral,dor,high,low,front,back,tense,suction
 
 // segments that represent common ipa symbols
 #[rustfmt::skip]
 pub const NATURAL_CLASSES: &[(&str, SegmentFeatures)] = &[
   // consonants
   ("C", SegmentFeatures::from_features(
-    [NEG,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
+    [NEG,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
   ),
   // vowels
   ("V", SegmentFeatures::from_features(
-    [POS,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
+    [POS,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
   ),
   // sibilant : [+cons+cor+strident]
   ("S", SegmentFeatures::from_features(
-    [UNDEF,UNDEF,POS,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,POS,UNDEF,UNDEF,POS,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
+    [UNDEF,UNDEF,POS,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,POS,UNDEF,UNDEF,POS,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
   ),
   // fricative : [+cons-son+cont]
   ("F", SegmentFeatures::from_features(
-    [UNDEF,UNDEF,POS,NEG,POS,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
+    [UNDEF,UNDEF,POS,NEG,POS,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF,UNDEF])
   ),
 ];
+
+/// A natural class, defined by a feature matrix like `[+nasal]`, or by a list of segments like
+/// `{p,t,k,q}`
+#[derive(Debug, Clone, PartialEq)]
+pub enum NaturalClass {
+    Features(SegmentFeatures),
+    /// the segments of the class, as written in its definition
+    Segments(Vec<String>),
+}
+
+impl NaturalClass {
+    /// Parse a class definition: a segment, usually a feature matrix like `[+nasal]`, or a list
+    /// of segments within braces, like `{p,t,k,q}`
+    pub fn parse(definition: &str) -> Result<Self> {
+        let parse_one = |text: &str| match parse_segment(text) {
+            Ok(("", seg)) => Ok(seg),
+            _ => Err(Error::segment(format!("\"{text}\" isn't a segment"), None)),
+        };
+
+        let definition = definition.trim();
+        match definition
+            .strip_prefix('{')
+            .and_then(|d| d.strip_suffix('}'))
+        {
+            Some(list) => {
+                let segments: Vec<&str> = list.split(',').map(str::trim).collect();
+                for segment in &segments {
+                    parse_one(segment)?;
+                }
+                Ok(Self::Segments(
+                    segments.into_iter().map(str::to_string).collect(),
+                ))
+            }
+            None => parse_one(definition).map(Self::Features),
+        }
+    }
+}
+
+/// Natural classes by name. A name is an uppercase letter followed by letters or digits, like
+/// `N` or `Nas`. When names overlap, like `N` and `Nas`, the longest one is matched first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NaturalClasses {
+    classes: Vec<(String, NaturalClass)>,
+}
+
+impl NaturalClasses {
+    /// The classes of `NATURAL_CLASSES`
+    pub fn builtin() -> Self {
+        Self {
+            classes: NATURAL_CLASSES
+                .iter()
+                .map(|(name, seg)| (name.to_string(), NaturalClass::Features(seg.clone())))
+                .collect(),
+        }
+    }
+
+    /// Add a class, or replace the class with the same name
+    pub fn define(&mut self, name: &str, class: NaturalClass) -> Result<()> {
+        if !is_class_name(name) {
+            return Err(Error::segment(
+                format!(
+                    "invalid class name \"{name}\", it should be an uppercase letter followed by letters or digits"
+                ),
+                None,
+            ));
+        }
+        match self.classes.iter_mut().find(|(n, _)| n == name) {
+            Some((_, existing)) => *existing = class,
+            None => self.classes.push((name.to_string(), class)),
+        }
+        Ok(())
+    }
+
+    /// Add the classes of `other`, replacing the classes with the same names
+    pub fn extend(&mut self, other: &NaturalClasses) {
+        for (name, class) in &other.classes {
+            match self.classes.iter_mut().find(|(n, _)| n == name) {
+                Some((_, existing)) => *existing = class.clone(),
+                None => self.classes.push((name.clone(), class.clone())),
+            }
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&NaturalClass> {
+        self.iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, class)| class)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &NaturalClass)> {
+        self.classes
+            .iter()
+            .map(|(name, class)| (name.as_str(), class))
+    }
+
+    /// the classes defined by a feature matrix
+    pub fn features(&self) -> impl Iterator<Item = (&str, &SegmentFeatures)> {
+        self.iter().filter_map(|(name, class)| match class {
+            NaturalClass::Features(seg) => Some((name, seg)),
+            NaturalClass::Segments(_) => None,
+        })
+    }
+
+    /// The class with the longest name at the start of `input`
+    pub fn longest_prefix(&self, input: &str) -> Option<(&str, &NaturalClass)> {
+        self.iter()
+            .filter(|(name, _)| input.starts_with(name))
+            .max_by_key(|(name, _)| name.len())
+    }
+}
+
+/// true if `name` can name a class, like `N` or `Nas`
+pub fn is_class_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_uppercase()) && chars.all(|c| c.is_ascii_alphanumeric())
+}
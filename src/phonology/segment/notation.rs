@@ -0,0 +1,70 @@
+use crate::phonology::syllable::SyllableFeatures;
+
+/// The stress marks of IPA, with the stress they mark. `'` and `,` are ASCII stand-ins.
+const IPA_STRESS_MARKS: &[(char, SyllableFeatures)] = &[
+    ('ˈ', SyllableFeatures::PRIMARY_STRESS),
+    ('ˌ', SyllableFeatures::SECONDARY_STRESS),
+    ('\'', SyllableFeatures::PRIMARY_STRESS),
+    (',', SyllableFeatures::SECONDARY_STRESS),
+];
+
+/// The stress marks of X-SAMPA, with the stress they mark
+const XSAMPA_STRESS_MARKS: &[(char, SyllableFeatures)] = &[
+    ('"', SyllableFeatures::PRIMARY_STRESS),
+    ('%', SyllableFeatures::SECONDARY_STRESS),
+];
+
+/// The symbols segments are written with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Notation {
+    /// IPA symbols, like `t͡ʃæ`. Syllables are stressed with `ˈ` (or `'`), and get secondary
+    /// stress with `ˌ` (or `,`).
+    #[default]
+    Ipa,
+    /// X-SAMPA symbols, like `tS{`. Syllables are stressed with `"` and get secondary stress
+    /// with `%`, and `'` is palatalization.
+    /// The natural class symbols (`C`, `V`...) are X-SAMPA symbols, so they aren't available.
+    XSampa,
+}
+
+impl Notation {
+    /// the mark written before a syllable with primary stress
+    pub fn stress_mark(&self) -> char {
+        match self {
+            Notation::Ipa => 'ˈ',
+            Notation::XSampa => '"',
+        }
+    }
+
+    /// the mark written before a syllable with secondary stress
+    pub fn secondary_stress_mark(&self) -> char {
+        match self {
+            Notation::Ipa => 'ˌ',
+            Notation::XSampa => '%',
+        }
+    }
+
+    /// the stress marked by `mark`, if it's a stress mark of this notation
+    pub fn stress_of_mark(&self, mark: char) -> Option<SyllableFeatures> {
+        let marks = match self {
+            Notation::Ipa => IPA_STRESS_MARKS,
+            Notation::XSampa => XSAMPA_STRESS_MARKS,
+        };
+        marks
+            .iter()
+            .find(|(m, _)| *m == mark)
+            .map(|(_, stress)| stress.clone())
+    }
+
+    /// The notation selected by the delimiters around `input`: `[...]` for IPA, `"..."` for
+    /// X-SAMPA. Returns the notation with the text between the delimiters.
+    pub fn strip_delimiters(input: &str) -> Option<(Notation, &str)> {
+        if let Some(inner) = input.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            return Some((Notation::Ipa, inner));
+        }
+        input
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+            .map(|inner| (Notation::XSampa, inner))
+    }
+}
@@ -1,30 +1,58 @@
+use std::sync::Arc;
+
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::character::complete::space0;
-use nom::combinator::{map, opt};
+use nom::character::complete::{one_of, satisfy, space0};
+use nom::combinator::{map, map_opt, opt, value};
 use nom::error::{Error, ErrorKind};
 use nom::multi::{many0, separated_list1};
-use nom::sequence::delimited;
+use nom::sequence::{delimited, preceded};
 use nom::{Err, IResult, Parser as _};
 use unicode_normalization::UnicodeNormalization;
 
-use crate::phonology::feature::{Feature, FeatureState};
+use crate::phonology::feature::{Feature, FeatureState, GREEK_VARIABLES, Variable};
 use crate::phonology::segment::{
-    DIACRITICS, IPA_BASES, NATURAL_CLASSES, SEG_FEATURE_NAMES, SegmentFeatures,
+    NaturalClass, NaturalClasses, Notation, SEG_FEATURE_NAMES, SegmentFeatures, SegmentTable,
+    XSAMPA_BASES, XSAMPA_DIACRITICS,
 };
 
 /// return a segment from either an ipa character, or a phonological class. this may be
-/// followed by a feature set within brackets.
+/// followed by a feature set within brackets. a feature set within brackets can also stand alone,
+/// ex: "[+cons-son]"
 /// no whitespace allowed
 pub fn parse_segment(input: &str) -> IResult<&str, SegmentFeatures> {
-    let mut parser = map(
-        (
-            alt((
-                with_ipa_diacritics(parse_ipa_base),
-                with_ipa_diacritics(parse_natural_class),
-            )),
-            opt(delimited(tag("["), parse_segment_feature_set, tag("]"))),
-        ),
+    let table = SegmentTable::current();
+    parse_segment_in(input, Notation::Ipa, &table.classes)
+}
+
+/// like `parse_segment_in`, with the symbols and natural classes of `table` instead of the
+/// current table
+pub fn parse_segment_with<'a>(
+    input: &'a str,
+    notation: Notation,
+    table: &Arc<SegmentTable>,
+) -> IResult<&'a str, SegmentFeatures> {
+    table.scope(|| parse_segment_in(input, notation, &table.classes))
+}
+
+/// like `parse_segment`, with the symbols of `notation` and the natural classes of `classes`.
+/// In X-SAMPA, there are no natural classes.
+/// ex: "tS_w", "a[+long]" (X-SAMPA)
+pub fn parse_segment_in<'a>(
+    input: &'a str,
+    notation: Notation,
+    classes: &NaturalClasses,
+) -> IResult<&'a str, SegmentFeatures> {
+    let base = |i: &'a str| match notation {
+        Notation::Ipa => alt((
+            with_ipa_diacritics(parse_ipa_base),
+            with_ipa_diacritics(|i| parse_natural_class_in(i, classes)),
+        ))
+        .parse(i),
+        Notation::XSampa => with_xsampa_diacritics(parse_xsampa_base).parse(i),
+    };
+    let with_base = map(
+        (base, opt(parse_bracketed_feature_set)),
         // apply feature set in brackets to the ipa symbol or class symbol
         |(mut base, features)| {
             if let Some(features) = features {
@@ -33,6 +61,15 @@ pub fn parse_segment(input: &str) -> IResult<&str, SegmentFeatures> {
             base
         },
     );
+    let mut parser = alt((with_base, parse_bracketed_feature_set));
+
+    parser.parse(input)
+}
+
+/// a feature set within brackets
+/// ex. "[+voi-delrel]"
+pub fn parse_bracketed_feature_set(input: &str) -> IResult<&str, SegmentFeatures> {
+    let mut parser = delimited(tag("["), parse_segment_feature_set, tag("]"));
 
     parser.parse(input)
 }
@@ -41,18 +78,8 @@ pub fn parse_segment(input: &str) -> IResult<&str, SegmentFeatures> {
 /// ex: "b"
 /// should parse a bilabial voiced plosive
 pub(crate) fn parse_ipa_base(input: &str) -> IResult<&str, SegmentFeatures> {
-    let index = IPA_BASES.iter().position(|(symbol, _)| {
-        // normalize unicode to NFD form !
-        let input_norm = input.nfd().to_string();
-        let symbol_norm = symbol.nfd().to_string();
-        input_norm.starts_with(&symbol_norm)
-    });
-    match index {
-        Some(i) => {
-            let end = IPA_BASES[i].0.len();
-            let ipa_base = IPA_BASES[i].1.clone();
-            Ok((&input[end..], ipa_base))
-        }
+    match longest_ipa_base(&SegmentTable::current(), input) {
+        Some((end, ipa_base)) => Ok((&input[end..], ipa_base.clone())),
         None => {
             // unknown ipa base
             Err(Err::Error(Error::new(input, ErrorKind::Verify)))
@@ -60,21 +87,26 @@ pub(crate) fn parse_ipa_base(input: &str) -> IResult<&str, SegmentFeatures> {
     }
 }
 
+// The base of `table` at the start of `input`, with its length in bytes. The longest symbol
+// is taken, so affricates like "t͡ʃ" aren't read as "t", and the first one of the table on ties.
+pub(crate) fn longest_ipa_base<'t>(
+    table: &'t SegmentTable,
+    input: &str,
+) -> Option<(usize, &'t SegmentFeatures)> {
+    table
+        .bases
+        .iter()
+        .enumerate()
+        .filter_map(|(i, (symbol, seg))| nfd_prefix_len(input, symbol).map(|end| (i, end, seg)))
+        .max_by_key(|(i, end, _)| (*end, std::cmp::Reverse(*i)))
+        .map(|(_, end, seg)| (end, seg))
+}
+
 /// Parse a diacritic at the beginning of `input`,
 /// returning the diacritic's features with remaining input
 pub(crate) fn parse_ipa_diacritic(input: &str) -> IResult<&str, SegmentFeatures> {
-    let index = DIACRITICS.iter().position(|(symbol, _)| {
-        // normalize unicode to NFD form !
-        let input_norm = input.nfd().to_string();
-        let symbol_norm = symbol.nfd().to_string();
-        input_norm.starts_with(&symbol_norm)
-    });
-    match index {
-        Some(i) => {
-            let end = DIACRITICS[i].0.len_utf8();
-            let ipa_base = DIACRITICS[i].1.clone();
-            Ok((&input[end..], ipa_base))
-        }
+    match first_ipa_diacritic(&SegmentTable::current(), input) {
+        Some((end, diacritic)) => Ok((&input[end..], diacritic.clone())),
         None => {
             // unknown ipa diacritic
             Err(Err::Error(Error::new(input, ErrorKind::Verify)))
@@ -82,6 +114,92 @@ pub(crate) fn parse_ipa_diacritic(input: &str) -> IResult<&str, SegmentFeatures>
     }
 }
 
+// The diacritic of `table` at the start of `input`, with its length in bytes
+pub(crate) fn first_ipa_diacritic<'t>(
+    table: &'t SegmentTable,
+    input: &str,
+) -> Option<(usize, &'t SegmentFeatures)> {
+    table.diacritics.iter().find_map(|(symbol, seg)| {
+        nfd_prefix_len(input, symbol.encode_utf8(&mut [0; 4])).map(|end| (end, seg))
+    })
+}
+
+/// Parse an X-SAMPA symbol, no diacritics, no extra features
+/// ex: "tS" for t͡ʃ
+pub(crate) fn parse_xsampa_base(input: &str) -> IResult<&str, SegmentFeatures> {
+    // the IPA symbols written the same way in X-SAMPA, then the X-SAMPA specific ones, which win
+    // ties. take the longest symbol, so "t_S" isn't read as "t"
+    let table = SegmentTable::current();
+    let ascii_bases = table
+        .bases
+        .iter()
+        .filter(|(symbol, _)| symbol.is_ascii())
+        .map(|(symbol, _)| (symbol.as_str(), symbol.as_str()));
+    let found = ascii_bases
+        .chain(XSAMPA_BASES.iter().copied())
+        .filter(|(xsampa, _)| input.starts_with(xsampa))
+        .filter_map(|(xsampa, ipa)| ipa_base(ipa).map(|seg| (xsampa.len(), seg)))
+        .max_by_key(|(end, _)| *end);
+    match found {
+        Some((end, base)) => Ok((&input[end..], base)),
+        None => Err(Err::Error(Error::new(input, ErrorKind::Verify))),
+    }
+}
+
+/// Parse an X-SAMPA diacritic at the beginning of `input`,
+/// returning the diacritic's features with remaining input
+pub(crate) fn parse_xsampa_diacritic(input: &str) -> IResult<&str, SegmentFeatures> {
+    let table = SegmentTable::current();
+    // the longest diacritic, so ":\\" isn't read as ":"
+    let found = XSAMPA_DIACRITICS
+        .iter()
+        .filter(|(xsampa, _)| input.starts_with(xsampa))
+        .filter_map(|(xsampa, ipa)| {
+            table
+                .diacritics
+                .iter()
+                .find(|(d, _)| d == ipa)
+                .map(|(_, seg)| (xsampa.len(), seg))
+        })
+        .rev()
+        .max_by_key(|(end, _)| *end);
+    match found {
+        Some((end, diacritic)) => Ok((&input[end..], diacritic.clone())),
+        None => Err(Err::Error(Error::new(input, ErrorKind::Verify))),
+    }
+}
+
+/// the features of the IPA base written exactly `symbol`
+fn ipa_base(symbol: &str) -> Option<SegmentFeatures> {
+    SegmentTable::current()
+        .bases
+        .iter()
+        .find(|(base, _)| nfd_prefix_len(symbol, base) == Some(symbol.len()))
+        .map(|(_, seg)| seg.clone())
+}
+
+/// Length in bytes of the prefix of `input` that is canonically equivalent to `symbol`.
+/// Both are compared in NFD form, and the prefix always ends on a character boundary.
+/// see https://www.unicode.org/reports/tr15/#Canon_Compat_Equivalence
+pub(crate) fn nfd_prefix_len(input: &str, symbol: &str) -> Option<usize> {
+    // most symbols start with another character: skip them without allocating
+    if symbol.chars().next()?.nfd().next() != input.chars().next()?.nfd().next() {
+        return None;
+    }
+    let symbol_norm: String = symbol.nfd().collect();
+    let mut prefix_norm = String::new();
+    for (idx, c) in input.char_indices() {
+        prefix_norm.extend(c.nfd());
+        if prefix_norm == symbol_norm {
+            return Some(idx + c.len_utf8());
+        }
+        if !symbol_norm.starts_with(&prefix_norm) {
+            return None;
+        }
+    }
+    None
+}
+
 /// parse diacritics following any segment parser.
 /// useful for diacritics after ipa characters as well as after natural class labels
 /// ex : "t̪"
@@ -92,33 +210,52 @@ pub(crate) fn with_ipa_diacritics<'a, P>(
 where
     P: nom::Parser<&'a str, Output = SegmentFeatures, Error = Error<&'a str>>,
 {
-    map(
-        (parser, many0(parse_ipa_diacritic)),
-        |(mut base, diacritics)| {
-            for d in diacritics {
-                base = base + d;
-            }
-            base
-        },
-    )
+    with_diacritics(parser, parse_ipa_diacritic)
+}
+
+/// like `with_ipa_diacritics`, for X-SAMPA diacritics
+/// ex : "t_d"
+pub(crate) fn with_xsampa_diacritics<'a, P>(
+    parser: P,
+) -> impl nom::Parser<&'a str, Output = SegmentFeatures, Error = Error<&'a str>>
+where
+    P: nom::Parser<&'a str, Output = SegmentFeatures, Error = Error<&'a str>>,
+{
+    with_diacritics(parser, parse_xsampa_diacritic)
+}
+
+fn with_diacritics<'a, P, D>(
+    parser: P,
+    diacritic: D,
+) -> impl nom::Parser<&'a str, Output = SegmentFeatures, Error = Error<&'a str>>
+where
+    P: nom::Parser<&'a str, Output = SegmentFeatures, Error = Error<&'a str>>,
+    D: nom::Parser<&'a str, Output = SegmentFeatures, Error = Error<&'a str>>,
+{
+    map((parser, many0(diacritic)), |(mut base, diacritics)| {
+        for d in diacritics {
+            base = base + d;
+        }
+        base
+    })
 }
 
 /// parse a natural class
 pub fn parse_natural_class(class_symbol: &str) -> IResult<&str, SegmentFeatures> {
-    let index = NATURAL_CLASSES.iter().position(|(symbol, _)| {
-        // normalizing to NFD not necessary here...
-        class_symbol.starts_with(symbol)
-    });
-    match index {
-        Some(i) => {
-            let end = NATURAL_CLASSES[i].0.len();
-            let ipa_base = NATURAL_CLASSES[i].1.clone();
-            Ok((&class_symbol[end..], ipa_base))
-        }
-        None => {
-            // unknown ipa diacritic
-            Err(Err::Error(Error::new(class_symbol, ErrorKind::Verify)))
-        }
+    let table = SegmentTable::current();
+    parse_natural_class_in(class_symbol, &table.classes)
+}
+
+/// parse a natural class of `classes` defined by a feature matrix. classes defined by a list of
+/// segments are branches in rules, not segments (see `NaturalClass`).
+pub fn parse_natural_class_in<'a>(
+    class_symbol: &'a str,
+    classes: &NaturalClasses,
+) -> IResult<&'a str, SegmentFeatures> {
+    // normalizing to NFD not necessary here, class names are ASCII
+    match classes.longest_prefix(class_symbol) {
+        Some((name, NaturalClass::Features(seg))) => Ok((&class_symbol[name.len()..], seg.clone())),
+        _ => Err(Err::Error(Error::new(class_symbol, ErrorKind::Verify))),
     }
 }
 
@@ -140,27 +277,58 @@ pub fn parse_segment_feature_set(s: &str) -> IResult<&str, SegmentFeatures> {
     })
 }
 
-/// parse a feature name with plus or minus sign before
-/// ex: "+delrel"
+/// parse a feature name with plus or minus sign before, or an alpha-notation variable
+/// ex: "+delrel", "αvoi", "-βback", "@avoi"
 fn parse_segment_feature(s: &str) -> IResult<&str, SegmentFeatures> {
-    let mut parser = (alt((tag("+"), tag("-"))), parse_feature_tag);
+    let mut parser = (parse_feature_value, parse_feature_tag);
 
-    let (remainder, (sign, feature)) = parser.parse(s)?;
+    let (remainder, (state, feature)) = parser.parse(s)?;
 
     let mut seg = SegmentFeatures::new_undef();
-    // set feature
-    if sign == "+" {
-        seg.features[feature as usize] = FeatureState::POS;
-    } else if sign == "-" {
-        seg.features[feature as usize] = FeatureState::NEG;
-    }
+    seg.features[feature as usize] = state;
 
     Ok((remainder, seg))
 }
 
+/// parse the value given to a feature in a feature set: `+`, `-`, a variable like `α`, or a
+/// negated variable like `-α`
+pub fn parse_feature_value(s: &str) -> IResult<&str, FeatureState> {
+    let mut parser = alt((
+        map(preceded(tag("-"), parse_variable), FeatureState::NALPHA),
+        map(parse_variable, FeatureState::ALPHA),
+        value(FeatureState::POS, tag("+")),
+        value(FeatureState::NEG, tag("-")),
+    ));
+
+    parser.parse(s)
+}
+
+/// parse an alpha-notation variable, either a greek letter (`α`) or `@` and a letter (`@a`)
+pub fn parse_variable(s: &str) -> IResult<&str, Variable> {
+    let greek = map_opt(one_of(&GREEK_VARIABLES[..]), |c| {
+        GREEK_VARIABLES
+            .iter()
+            .position(|g| *g == c)
+            .map(|i| i as Variable)
+    });
+    let ascii = map(
+        preceded(tag("@"), satisfy(|c| c.is_ascii_lowercase())),
+        |c| (c as u8 - b'a') as Variable,
+    );
+    let mut parser = alt((greek, ascii));
+
+    parser.parse(s)
+}
+
 /// converts a feature name string to the corresponding u8 index
 pub fn parse_feature_tag(string: &str) -> IResult<&str, Feature> {
-    let index = SEG_FEATURE_NAMES.iter().position(|s| string.starts_with(s));
+    // take the longest name, so "labdent" isn't read as "lab"
+    let index = SEG_FEATURE_NAMES
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| string.starts_with(*s))
+        .max_by_key(|(_, s)| s.len())
+        .map(|(i, _)| i);
     match index {
         Some(i) => {
             let end = SEG_FEATURE_NAMES[i].len();
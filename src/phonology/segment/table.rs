@@ -0,0 +1,185 @@
+use std::{
+    cell::RefCell,
+    sync::{Arc, LazyLock, RwLock},
+};
+
+use crate::error::*;
+use crate::phonology::{
+    feature::FeatureState,
+    segment::{DIACRITICS, IPA_BASES, NaturalClasses, SEG_FEATURE_NAMES, SegmentFeatures},
+};
+
+// the feature names of the Hayes feature CSV, in the order of `SEG_FEATURE_NAMES`
+// see https://linguistics.ucla.edu/people/hayes/IP/#features
+const HAYES_FEATURE_NAMES: [&str; SEG_FEATURE_NAMES.len()] = [
+    "syllabic",
+    "long",
+    "consonantal",
+    "sonorant",
+    "continuant",
+    "delayed release",
+    "approximant",
+    "tap",
+    "trill",
+    "nasal",
+    "voice",
+    "spread gl",
+    "constr gl",
+    "labial",
+    "round",
+    "labiodental",
+    "coronal",
+    "anterior",
+    "distributed",
+    "strident",
+    "lateral",
+    "dorsal",
+    "high",
+    "low",
+    "front",
+    "back",
+    "tense",
+    // not in the spreadsheet
+    "suction",
+];
+
+static CURRENT: LazyLock<RwLock<Arc<SegmentTable>>> =
+    LazyLock::new(|| RwLock::new(Arc::new(SegmentTable::builtin())));
+
+thread_local! {
+    // the table of the innermost `SegmentTable::scope` of this thread, used instead of `CURRENT`
+    static SCOPED: RefCell<Option<Arc<SegmentTable>>> = const { RefCell::new(None) };
+}
+
+/// The symbols segments are parsed from and formatted to: IPA bases, diacritics and natural
+/// classes. `parse_segment` and `format_segment` use the current table, which is the built-in
+/// one until another table is installed. `parse_segment_with` and `format_segment_with` take the
+/// table to use instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentTable {
+    pub bases: Vec<(String, SegmentFeatures)>,
+    pub diacritics: Vec<(char, SegmentFeatures)>,
+    pub classes: NaturalClasses,
+}
+
+impl SegmentTable {
+    /// The table of `IPA_BASES`, `DIACRITICS` and `NATURAL_CLASSES`
+    pub fn builtin() -> Self {
+        Self {
+            bases: IPA_BASES
+                .iter()
+                .map(|(symbol, seg)| (symbol.to_string(), seg.clone()))
+                .collect(),
+            diacritics: DIACRITICS.to_vec(),
+            classes: NaturalClasses::builtin(),
+        }
+    }
+
+    /// The table used to parse and format segments
+    pub fn current() -> Arc<SegmentTable> {
+        SCOPED
+            .with_borrow(|scoped| scoped.clone())
+            .unwrap_or_else(|| CURRENT.read().unwrap_or_else(|e| e.into_inner()).clone())
+    }
+
+    /// Use this table to parse and format segments within `f`, on this thread only. Unlike
+    /// `install`, other threads keep parsing with their own table.
+    pub fn scope<R>(self: &Arc<Self>, f: impl FnOnce() -> R) -> R {
+        // restores the outer table when `f` returns or panics
+        struct Restore(Option<Arc<SegmentTable>>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                SCOPED.set(self.0.take());
+            }
+        }
+
+        let _restore = Restore(SCOPED.replace(Some(self.clone())));
+        f()
+    }
+
+    /// Use this table to parse and format segments, in the whole program
+    pub fn install(self) {
+        *CURRENT.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(self);
+    }
+
+    /// A table with the bases of a feature CSV (see `extend_from_csv`), and the built-in
+    /// diacritics and classes
+    pub fn from_csv(csv: &str) -> Result<Self> {
+        let mut table = Self {
+            bases: vec![],
+            ..Self::builtin()
+        };
+        table.extend_from_csv(csv)?;
+        Ok(table)
+    }
+
+    /// Add the bases of a feature CSV, like the Hayes feature spreadsheet. The first row names
+    /// the features, with their names in rules (`voi`) or in the spreadsheet (`voice`), and the
+    /// first column has the symbols. Values are `+`, `-`, `0` for not applicable, or empty for
+    /// undefined. Unknown feature columns are ignored, and a symbol already in the table gets
+    /// the new features.
+    ///
+    /// ```text
+    /// symbol,syllabic,consonantal,sonorant,voice
+    /// ɐ,+,-,+,+
+    /// ```
+    pub fn extend_from_csv(&mut self, csv: &str) -> Result<()> {
+        let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+        let Some(header) = lines.next() else {
+            return Ok(());
+        };
+        let columns: Vec<Option<usize>> = header.split(',').skip(1).map(feature_index).collect();
+
+        for line in lines {
+            let mut cells = line.split(',');
+            let symbol = cells.next().unwrap_or_default().trim();
+            if symbol.is_empty() {
+                return Err(Error::segment("missing symbol", Some(Span::of(csv, line))));
+            }
+
+            let mut features = SegmentFeatures::new_undef();
+            for (cell, column) in cells.zip(&columns) {
+                let state = match cell.trim() {
+                    "+" => FeatureState::POS,
+                    "-" => FeatureState::NEG,
+                    "0" => FeatureState::NA,
+                    "" => FeatureState::UNDEF,
+                    _ => {
+                        return Err(Error::segment(
+                            format!("invalid feature value \"{}\"", cell.trim()),
+                            Some(Span::of(csv, cell)),
+                        ));
+                    }
+                };
+                if let Some(i) = column {
+                    features.features[*i] = state;
+                }
+            }
+            self.insert_base(symbol, features);
+        }
+        Ok(())
+    }
+
+    /// Add a base symbol, or change the features of an existing one
+    pub fn insert_base(&mut self, symbol: &str, features: SegmentFeatures) {
+        match self.bases.iter_mut().find(|(s, _)| s == symbol) {
+            Some((_, existing)) => *existing = features,
+            None => self.bases.push((symbol.to_string(), features)),
+        }
+    }
+}
+
+impl Default for SegmentTable {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+/// index of the feature named `name` in a CSV header
+fn feature_index(name: &str) -> Option<usize> {
+    let name = name.trim().to_lowercase();
+    SEG_FEATURE_NAMES
+        .iter()
+        .position(|n| *n == name)
+        .or_else(|| HAYES_FEATURE_NAMES.iter().position(|n| *n == name))
+}
@@ -2,13 +2,18 @@
 #[cfg(test)]
 mod segment_tests {
 
+    use std::sync::Arc;
+
     use nom::Parser as _;
 
     use crate::phonology::feature::FeatureState::*;
     use crate::phonology::segment::{
-        SegmentFeatures, format, parse_ipa_base, parse_segment, parse_segment_feature_set,
-        with_ipa_diacritics,
+        CONS, Contradiction, HIGH, LAB, LOW, NaturalClass, NaturalClasses, Notation, ROUND,
+        SUCTION, SYL, SegmentFeatures, SegmentTable, VOI, XSAMPA_BASES, format,
+        format_segment_with, parse_ipa_base, parse_segment, parse_segment_feature_set,
+        parse_segment_in, parse_segment_with, parse_xsampa_base, with_ipa_diacritics,
     };
+    use crate::phonology::string::PhonoString;
 
     #[test]
     fn test_segment_from_ipa() {
@@ -18,7 +23,7 @@ mod segment_tests {
             seg,
             SegmentFeatures::from_features([
                 POS, NEG, NEG, POS, POS, NEG, POS, NEG, NEG, NEG, POS, NEG, NEG, NEG, NEG, NEG,
-                NEG, NA, NA, NA, NEG, POS, NEG, POS, NEG, NEG, NA
+                NEG, NA, NA, NA, NEG, POS, NEG, POS, NEG, NEG, NA, NEG
             ]),
         );
     }
@@ -32,7 +37,7 @@ mod segment_tests {
             SegmentFeatures::from_features([
                 UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, NEG, UNDEF,
                 UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF,
-                UNDEF, POS, NEG,
+                UNDEF, POS, NEG, UNDEF,
             ])
         );
     }
@@ -46,7 +51,7 @@ mod segment_tests {
             seg,
             SegmentFeatures::from_features([
                 NEG, NEG, POS, NEG, POS, NEG, NEG, NEG, NEG, NEG, POS, NEG, NEG, NEG, NEG, NEG,
-                NEG, NA, NA, NA, NEG, POS, POS, POS, NA, NA, NA
+                NEG, NA, NA, NA, NEG, POS, POS, POS, NA, NA, NA, NEG
             ]),
         );
     }
@@ -60,7 +65,7 @@ mod segment_tests {
             seg,
             SegmentFeatures::from_features([
                 POS, NEG, POS, POS, POS, NEG, POS, NEG, NEG, NEG, POS, NEG, NEG, NEG, NEG, NEG,
-                NEG, NA, NA, NA, NEG, POS, NEG, POS, NEG, NEG, NA
+                NEG, NA, NA, NA, NEG, POS, NEG, POS, NEG, NEG, NA, NEG
             ]),
         );
     }
@@ -76,7 +81,7 @@ mod segment_tests {
             SegmentFeatures::from_features([
                 POS, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF,
                 UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF, UNDEF,
-                POS, UNDEF, UNDEF,
+                POS, UNDEF, UNDEF, UNDEF,
             ]),
         );
     }
@@ -89,7 +94,7 @@ mod segment_tests {
             seg,
             SegmentFeatures::from_features([
                 NEG, NEG, POS, NEG, NEG, POS, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NEG,
-                POS, POS, POS, NEG, NEG, NEG, NA, NA, NA, NA, NA
+                POS, POS, POS, NEG, NEG, NEG, NA, NA, NA, NA, NA, NEG
             ]),
         );
     }
@@ -99,7 +104,7 @@ mod segment_tests {
         // this could also be ɤʲ !
         let segment = SegmentFeatures::from_features([
             POS, NEG, NEG, POS, POS, NEG, POS, NEG, NEG, NEG, POS, NEG, NEG, NEG, NEG, NEG, NEG,
-            NA, NA, NA, NEG, POS, POS, NEG, POS, NEG, POS,
+            NA, NA, NA, NEG, POS, POS, NEG, POS, NEG, POS, NEG,
         ]);
         let result = format::format_segment(&segment);
         assert_eq!(result, "i",);
@@ -110,9 +115,264 @@ mod segment_tests {
         // this is lʲ !
         let segment = SegmentFeatures::from_features([
             NEG, NEG, POS, POS, POS, NEG, POS, NEG, NEG, NEG, POS, NEG, NEG, NEG, NEG, NEG, POS,
-            POS, NEG, NEG, POS, NEG, POS, NEG, POS, NEG, NA,
+            POS, NEG, NEG, POS, NEG, POS, NEG, POS, NEG, NA, NEG,
         ]);
         let result = format::format_segment(&segment);
         assert_eq!(result, "lʲ",);
     }
+
+    #[test]
+    fn test_parse_affricate_longest_match() {
+        let (remaining, seg) = parse_ipa_base("t͡ʃa").unwrap();
+        assert_eq!(remaining, "a");
+        assert_eq!(format::format_segment(&seg), "t͡ʃ");
+    }
+
+    #[test]
+    fn test_parse_feature_set_variables() {
+        let (remaining, seg) = parse_segment_feature_set("αvoi-βback@cnasal+labdent").unwrap();
+        assert_eq!(remaining, "");
+        assert_eq!(
+            seg,
+            SegmentFeatures::from_features([
+                UNDEF,
+                UNDEF,
+                UNDEF,
+                UNDEF,
+                UNDEF,
+                UNDEF,
+                UNDEF,
+                UNDEF,
+                UNDEF,
+                ALPHA(2),
+                ALPHA(0),
+                UNDEF,
+                UNDEF,
+                UNDEF,
+                UNDEF,
+                POS,
+                UNDEF,
+                UNDEF,
+                UNDEF,
+                UNDEF,
+                UNDEF,
+                UNDEF,
+                UNDEF,
+                UNDEF,
+                UNDEF,
+                NALPHA(1),
+                UNDEF,
+                UNDEF,
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_decomposed_base() {
+        // "ç" written as "c" and a combining cedilla
+        let (remaining, seg) = parse_ipa_base("c\u{327}a").unwrap();
+        assert_eq!(remaining, "a");
+        assert_eq!(seg, parse_ipa_base("ç").unwrap().1);
+    }
+
+    #[test]
+    fn test_parse_bare_feature_set() {
+        let (remaining, seg) = parse_segment("[+voi-back]a").unwrap();
+        assert_eq!(remaining, "a");
+        assert_eq!(seg, parse_segment_feature_set("+voi-back").unwrap().1);
+    }
+
+    #[test]
+    fn test_parse_xsampa_longest_match() {
+        let (remaining, seg) = parse_xsampa_base("t_Sa").unwrap();
+        assert_eq!(remaining, "a");
+        assert_eq!(seg, parse_ipa_base("t͡ʃ").unwrap().1);
+
+        let (remaining, seg) = parse_xsampa_base("r\\`a").unwrap();
+        assert_eq!(remaining, "a");
+        assert_eq!(seg, parse_ipa_base("ɻ").unwrap().1);
+    }
+
+    #[test]
+    fn test_parse_xsampa_with_diacritics() {
+        let (remaining, seg) = parse_segment_in(
+            "t_d_w[+delrel]",
+            Notation::XSampa,
+            &NaturalClasses::default(),
+        )
+        .unwrap();
+        assert_eq!(remaining, "");
+        assert_eq!(seg, parse_segment("t̪ʷ[+delrel]").unwrap().1);
+    }
+
+    #[test]
+    fn test_xsampa_bases_round_trip() {
+        for (xsampa, ipa) in XSAMPA_BASES {
+            let (remaining, seg) = parse_xsampa_base(xsampa).unwrap();
+            assert_eq!(remaining, "", "{xsampa}");
+            assert_eq!(seg, parse_ipa_base(ipa).unwrap().1, "{xsampa}");
+
+            let formatted = format::format_segment_xsampa(&seg);
+            assert_eq!(
+                parse_segment_in(&formatted, Notation::XSampa, &NaturalClasses::default())
+                    .unwrap()
+                    .1,
+                seg
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_xsampa() {
+        let seg = parse_segment("lʲ").unwrap().1;
+        assert_eq!(format::format_segment_xsampa(&seg), "l'");
+        let seg = parse_segment("ɛ̃").unwrap().1;
+        assert_eq!(format::format_segment_in(&seg, Notation::XSampa), "E~");
+    }
+
+    #[test]
+    fn test_segment_table_from_csv() {
+        let csv = "symbol,syllabic,consonantal,voi,stress\nɐ,+,-,+,-\nb,-,+,0,\n";
+        let table = SegmentTable::from_csv(csv).unwrap();
+        assert_eq!(table.bases.len(), 2);
+        assert_eq!(table.diacritics, SegmentTable::builtin().diacritics);
+
+        let (symbol, seg) = &table.bases[1];
+        assert_eq!(symbol, "b");
+        let mut expected = SegmentFeatures::new_undef();
+        expected.features[SYL as usize] = NEG;
+        expected.features[CONS as usize] = POS;
+        expected.features[VOI as usize] = NA;
+        assert_eq!(seg, &expected);
+
+        let err = SegmentTable::from_csv("symbol,voice\nb,x\n").unwrap_err();
+        assert_eq!(err.span().unwrap().range, 15..16);
+    }
+
+    #[test]
+    fn test_segment_table_with() {
+        // "a" with +tense, which no other symbol has
+        let (_, mut features) = parse_segment("a").unwrap();
+        features = features + parse_segment("[+tense]").unwrap().1;
+
+        let mut table = SegmentTable::builtin();
+        table.insert_base("ɐ", features.clone());
+        let table = Arc::new(table);
+        let parsed = parse_segment_with("ɐ", Notation::Ipa, &table).map(|(_, seg)| seg);
+        assert_eq!(parsed, Ok(features.clone()));
+        assert_eq!(format_segment_with(&features, Notation::Ipa, &table), "ɐ");
+
+        // other parsers keep the current table, even within a scope on another thread
+        table.scope(|| {
+            assert!(parse_segment("ɐ").is_ok());
+            let other = std::thread::spawn(|| parse_segment("ɐ").is_err());
+            assert!(other.join().unwrap());
+        });
+        assert!(parse_segment("ɐ").is_err());
+        assert_ne!(format::format_segment(&features), "ɐ");
+
+        let string = PhonoString::parse_with("ˈtɐ", Notation::Ipa, &table)
+            .unwrap()
+            .1;
+        assert_eq!(string.format_with(Notation::Ipa, &table), "ˈtɐ");
+    }
+
+    #[test]
+    fn test_user_natural_classes() {
+        let mut classes = NaturalClasses::builtin();
+        let nasal = NaturalClass::parse("[+nasal]").unwrap();
+        classes.define("Nas", nasal.clone()).unwrap();
+        classes
+            .define("N", NaturalClass::parse("{m, n}").unwrap())
+            .unwrap();
+        assert!(classes.define("nas", nasal).is_err());
+        assert!(NaturalClass::parse("{m,%}").is_err());
+
+        // the longest name, "Nas" isn't "N" followed by "as"
+        let (remaining, seg) = parse_segment_in("Nas[+voi]", Notation::Ipa, &classes).unwrap();
+        assert_eq!(remaining, "");
+        assert_eq!(seg, parse_segment("[+nasal+voi]").unwrap().1);
+        // list classes aren't segments
+        assert!(parse_segment_in("N", Notation::Ipa, &classes).is_err());
+        assert_eq!(
+            classes.get("N"),
+            Some(&NaturalClass::Segments(vec!["m".into(), "n".into()]))
+        );
+    }
+
+    #[test]
+    fn test_feature_geometry() {
+        for (symbol, seg) in SegmentTable::builtin().bases {
+            assert_eq!(seg.contradictions(), vec![], "{symbol}");
+        }
+
+        let (_, seg) = parse_segment("t[+round]").unwrap();
+        assert_eq!(
+            seg.contradictions(),
+            vec![Contradiction::Dependent {
+                node: LAB,
+                dependent: ROUND
+            }]
+        );
+        assert!(seg.validate().is_err());
+        let (_, vowel) = parse_segment("i[+low]").unwrap();
+        assert_eq!(
+            vowel.contradictions(),
+            vec![Contradiction::Exclusive(HIGH, LOW)]
+        );
+
+        let normalized = seg.normalized();
+        assert_eq!(normalized.features[ROUND as usize], NA);
+        assert_eq!(normalized.contradictions(), vec![]);
+        assert_eq!(format::format_segment(&normalized), "t");
+    }
+
+    #[test]
+    fn test_format_diacritics() {
+        for ipa in [
+            "n̥", "tʰ", "a̤", "a̰", "n̩", "i̯", "u̟", "lˠ", "tˤ", "ə˞", "ɛ̃ː", "tʷʰ", "n̩ː",
+        ] {
+            let seg = parse_segment(ipa).unwrap().1;
+            assert_eq!(format::format_segment(&seg), ipa);
+        }
+        // the features of these are the features of other symbols
+        for (ipa, expected) in [("e̝", "i"), ("ɪ̘", "i"), ("i̙", "ɪ"), ("aˑ", "aː")] {
+            let seg = parse_segment(ipa).unwrap().1;
+            assert_eq!(format::format_segment(&seg), expected);
+        }
+        let (remaining, seg) =
+            parse_segment_in("a:\\_h", Notation::XSampa, &NaturalClasses::default()).unwrap();
+        assert_eq!(remaining, "");
+        assert_eq!(seg, parse_segment("aˑʰ").unwrap().1);
+    }
+
+    #[test]
+    fn test_diacritic_index_round_trip() {
+        for (seg, ipa) in format::build_diacritic_index(&SegmentTable::builtin()) {
+            assert_eq!(parse_segment(&ipa).unwrap(), ("", seg.clone()), "{ipa}");
+            let formatted = format::format_segment(&seg);
+            assert_eq!(parse_segment(&formatted).unwrap(), ("", seg), "{formatted}");
+        }
+    }
+
+    #[test]
+    fn test_implosives_clicks_prenasalized() {
+        for ipa in [
+            "ɓ", "ɗ", "ʄ", "ɠ", "ʛ", "ʘ", "ǀ", "ǃ", "ǂ", "ǁ", "ᵐb", "ⁿd", "ᵑɡ",
+        ] {
+            let (remaining, seg) = parse_segment(ipa).unwrap();
+            assert_eq!(remaining, "");
+            assert!(seg.is_complete(), "{ipa}");
+            assert_eq!(format::format_segment(&seg), ipa);
+        }
+        assert_eq!(
+            parse_segment("ɓ").unwrap().1.features[SUCTION as usize],
+            POS
+        );
+        // a nasalized stop is prenasalized
+        assert_eq!(
+            parse_segment("b̃").unwrap().1,
+            parse_segment("ᵐb").unwrap().1
+        );
+    }
 }
@@ -0,0 +1,72 @@
+// X-SAMPA symbols for the IPA symbols that aren't written the same way. The lowercase ASCII IPA
+// symbols, like "a" or "t", are the same in X-SAMPA.
+// see https://en.wikipedia.org/wiki/X-SAMPA
+
+/// X-SAMPA symbol and the corresponding IPA base symbol. When an IPA symbol has several X-SAMPA
+/// spellings, the first one is used for formatting.
+#[rustfmt::skip]
+pub const XSAMPA_BASES: &[(&str, &str)] = &[
+    // vowels
+    ("Q", "ɒ"), ("A", "ɑ"), ("&", "ɶ"), ("{", "æ"), ("V", "ʌ"), ("O", "ɔ"), ("7", "ɤ"),
+    ("@\\", "ɘ"), ("9", "œ"), ("@", "ə"), ("3\\", "ɞ"), ("2", "ø"), ("E", "ɛ"), ("8", "ɵ"),
+    ("M", "ɯ"), ("U", "ʊ"), ("1", "ɨ"), ("}", "ʉ"), ("Y", "ʏ"), ("I", "ɪ"),
+    // consonants
+    ("5", "ɫ"), ("N\\", "ɴ"), ("R\\", "ʀ"), ("J", "ɲ"), ("L", "ʎ"), ("N", "ŋ"),
+    ("N_-", "ŋ\u{320}"), ("L\\", "ʟ"), ("L\\_-", "ʟ\u{320}"), ("n`", "ɳ"), ("B\\", "ʙ"),
+    ("l`", "ɭ"), ("l\\", "ɺ"), ("r\\`", "ɻ"), ("r`", "ɽ"), ("4", "ɾ"), ("F", "ɱ"), ("?", "ʔ"),
+    ("X\\", "ħ"), ("?\\", "ʕ"), ("R", "ʁ"), ("X", "χ"), ("G\\", "ɢ"), ("s\\", "ɕ"),
+    ("J\\", "ɟ"), ("j\\", "ʝ"), ("C", "ç"), ("G", "ɣ"), ("G_-", "ɣ\u{320}"), ("g", "ɡ"),
+    ("g_-", "ɡ\u{320}"), ("z\\", "ʑ"), ("t`", "ʈ"), ("d`", "ɖ"), ("K", "ɬ"), ("z`", "ʐ"),
+    ("p\\", "ɸ"), ("s`", "ʂ"), ("Z", "ʒ"), ("S", "ʃ"), ("T", "θ"), ("K\\", "ɮ"), ("D", "ð"),
+    ("B", "β"), ("x\\", "ɧ"), ("M\\", "ɰ"), ("M\\_-", "ɰ\u{320}"), ("H", "ɥ"), ("r\\", "ɹ"),
+    ("v\\", "ʋ"), ("W", "ʍ"), ("h\\", "ɦ"),
+    // affricates and double articulations, with the tie bar `_`
+    ("tS", "t\u{361}ʃ"), ("dZ", "d\u{361}ʒ"),
+    ("t_S", "t\u{361}ʃ"), ("d_Z", "d\u{361}ʒ"), ("t_s", "t\u{361}s"), ("d_z", "d\u{361}z"),
+    ("t_s\\", "t\u{361}ɕ"), ("d_z\\", "d\u{361}ʑ"), ("t_K", "t\u{361}ɬ"), ("d_K\\", "d\u{361}ɮ"),
+    ("t_-_K_-", "t\u{320}\u{361}ɬ\u{320}"), ("d_-_K\\_-", "d\u{320}\u{361}ɮ\u{320}"),
+    ("t_d_s_d", "t\u{32a}\u{361}s\u{32a}"), ("t_d_K_d", "t\u{32a}\u{361}ɬ\u{32a}"),
+    ("d_d_z_d", "d\u{32a}\u{361}z\u{32a}"), ("d_d_K\\_d", "d\u{32a}\u{361}ɮ\u{32a}"),
+    ("t`_s`", "ʈ\u{361}ʂ"), ("d`_z`", "ɖ\u{361}ʐ"), ("p_f", "p\u{361}f"), ("b_v", "b\u{361}v"),
+    ("p_p\\", "p\u{361}ɸ"), ("b_B", "b\u{361}β"), ("t_d_T", "t\u{32a}\u{361}θ"),
+    ("c_C", "c\u{361}ç"), ("J\\_j\\", "ɟ\u{361}ʝ"), ("k_x", "k\u{361}x"),
+    ("k_-_x_-", "k\u{320}\u{361}x\u{320}"), ("g_G", "ɡ\u{361}ɣ"),
+    ("g_-_G_-", "ɡ\u{320}\u{361}ɣ\u{320}"), ("q_X", "q\u{361}χ"), ("G\\_R", "ɢ\u{361}ʁ"),
+    ("k_p", "k\u{361}p"), ("g_b", "ɡ\u{361}b"), ("p_t", "p\u{361}t"), ("b_d", "b\u{361}d"),
+    // implosives and clicks
+    ("b_<", "ɓ"), ("d_<", "ɗ"), ("J\\_<", "ʄ"), ("g_<", "ɠ"), ("G\\_<", "ʛ"),
+    ("O\\", "ʘ"), ("|\\", "ǀ"), ("!\\", "ǃ"), ("=\\", "ǂ"), ("|\\|\\", "ǁ"),
+];
+
+/// X-SAMPA diacritic and the corresponding IPA diacritic
+#[rustfmt::skip]
+pub const XSAMPA_DIACRITICS: &[(&str, char)] = &[
+    ("~", '\u{0303}'),    // nasalization
+    (":", '\u{02D0}'),    // long
+    ("_o", '\u{031E}'),   // lowered
+    ("_o", '\u{02D5}'),
+    ("_w", '\u{02B7}'),   // labialization
+    ("_d", '\u{032A}'),   // dental
+    ("_>", '\u{02BC}'),   // ejective
+    ("'", '\u{02B2}'),    // palatalization
+    ("_j", '\u{02B2}'),
+    ("_0", '\u{0325}'),   // voiceless
+    ("_0", '\u{030A}'),
+    ("_h", '\u{02B0}'),   // aspiration
+    ("_t", '\u{0324}'),   // breathy voice
+    ("_k", '\u{0330}'),   // creaky voice
+    ("=", '\u{0329}'),    // syllabic
+    ("_=", '\u{0329}'),
+    ("=", '\u{030D}'),
+    ("_^", '\u{032F}'),   // non-syllabic
+    ("_r", '\u{031D}'),   // raised
+    ("_r", '\u{02D4}'),
+    ("_+", '\u{031F}'),   // advanced
+    ("_-", '\u{0320}'),   // retracted
+    ("_A", '\u{0318}'),   // advanced tongue root
+    ("_q", '\u{0319}'),   // retracted tongue root
+    ("_G", '\u{02E0}'),   // velarization
+    ("_?\\", '\u{02E4}'), // pharyngealization
+    (":\\", '\u{02D1}'),  // half-long
+    ("`", '\u{02DE}'),    // rhoticity
+];
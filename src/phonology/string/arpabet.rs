@@ -0,0 +1,199 @@
+use std::{fs, path::Path};
+
+use crate::error::*;
+use crate::phonology::{
+    feature::FeatureState,
+    segment::{SegmentFeatures, parse_segment},
+    string::{PhonoString, SyllabifyOpts, split_syllables_at},
+    syllable::{PRIMARY, STRESS, SyllableFeatures},
+    tree::Depth3Tree,
+};
+
+// ARPABET symbols and the IPA segments they are made of. Vowels are the symbols that take a
+// stress digit. When several symbols have the same segments, the first one is used for
+// exporting, and a symbol with its stress digit (like "AH0") is tried before the bare symbol.
+// see http://www.speech.cs.cmu.edu/cgi-bin/cmudict
+#[rustfmt::skip]
+const ARPABET_VOWELS: &[(&str, &str)] = &[
+    ("AA", "ɑ"), ("AE", "æ"), ("AH0", "ə"), ("AH", "ʌ"), ("AH", "ə"), ("AO", "ɔ"), ("AW", "aʊ"),
+    ("AY", "aɪ"), ("EH", "ɛ"), ("ER", "ɹ[+syl]"), ("EY", "eɪ"), ("IH", "ɪ"), ("IY", "i"),
+    ("OW", "oʊ"), ("OY", "ɔɪ"), ("UH", "ʊ"), ("UW", "u"),
+];
+
+#[rustfmt::skip]
+const ARPABET_CONSONANTS: &[(&str, &str)] = &[
+    ("B", "b"), ("CH", "t͡ʃ"), ("D", "d"), ("DH", "ð"), ("F", "f"), ("G", "ɡ"), ("HH", "h"),
+    ("JH", "d͡ʒ"), ("K", "k"), ("L", "l"), ("M", "m"), ("N", "n"), ("NG", "ŋ"), ("P", "p"),
+    ("R", "ɹ"), ("S", "s"), ("SH", "ʃ"), ("T", "t"), ("TH", "θ"), ("V", "v"), ("W", "w"),
+    ("Y", "j"), ("Z", "z"), ("ZH", "ʒ"),
+];
+
+impl PhonoString {
+    /// Convert a sequence of ARPABET tokens, like `["K", "AO1", "R", "P", "AH0", "S"]`, to a
+    /// one word string. Each vowel is the nucleus of a syllable, and the consonants between
+    /// vowels are split by the Maximal Onset Principle (see `PhonoString::syllabify`).
+    ///
+    /// The stress digit of a vowel sets the stress of its syllable: `0` is unstressed, `1` is
+    /// primary stress and `2` secondary stress. A vowel without a digit leaves it undefined.
+    /// A word has at least one syllable, so there must be at least one token.
+    pub fn from_arpabet<S: AsRef<str>>(tokens: &[S]) -> Result<Self> {
+        if tokens.is_empty() {
+            return Err(Error::segment("no ARPABET symbol in the word", None));
+        }
+        let mut segs = vec![];
+        let mut nuclei = vec![];
+        let mut stresses = vec![];
+        for token in tokens {
+            let token = token.as_ref();
+            let (token_segs, stress) = arpabet_segments(token).ok_or_else(|| {
+                Error::segment(format!("unknown ARPABET symbol \"{token}\""), None)
+            })?;
+            if let Some(stress) = stress {
+                nuclei.push(segs.len()..(segs.len() + token_segs.len()));
+                stresses.push(stress);
+            }
+            segs.extend(token_segs);
+        }
+
+        let mut tree = Depth3Tree::new();
+        tree.push_depth_0(());
+        let syllables = split_syllables_at(&segs, &nuclei, &SyllabifyOpts::default());
+        for (i, syl_segs) in syllables.into_iter().enumerate() {
+            let syl = stresses
+                .get(i)
+                .cloned()
+                .unwrap_or(SyllableFeatures::new_undef());
+            tree.push_depth_1(syl);
+            for seg in &segs[syl_segs] {
+                tree.push_depth_2(seg.clone());
+            }
+        }
+
+        Ok(Self::new(tree))
+    }
+
+    /// Convert the string to ARPABET tokens. Vowels get the stress digit of their syllable: `2`
+    /// for secondary stress (`[+stress -primary]`), `1` for other stressed syllables, otherwise
+    /// `0`. Boundaries are lost.
+    pub fn to_arpabet(&self) -> Result<Vec<String>> {
+        let mut tokens = vec![];
+        for (syl_idx, (syl, _)) in self.tree.layer_3().iter().enumerate() {
+            let segs: Vec<&SegmentFeatures> = self.tree.layer_4()[self.tree.children_4(syl_idx)]
+                .iter()
+                .map(|(seg, _)| seg)
+                .collect();
+            let stressed = syl.features[STRESS as usize] == FeatureState::POS;
+            let digit = match (stressed, syl.features[PRIMARY as usize]) {
+                (true, FeatureState::NEG) => "2",
+                (true, _) => "1",
+                (false, _) => "0",
+            };
+
+            let mut rest = &segs[..];
+            while !rest.is_empty() {
+                let (len, token, is_vowel) = arpabet_token(rest).ok_or_else(|| {
+                    Error::segment(format!("no ARPABET symbol for [{}]", rest[0]), None)
+                })?;
+                if is_vowel {
+                    tokens.push(format!("{token}{digit}"));
+                } else {
+                    tokens.push(token.to_string());
+                }
+                rest = &rest[len..];
+            }
+        }
+        Ok(tokens)
+    }
+}
+
+/// Parse a pronunciation dictionary in the CMUdict format: one word per line, followed by its
+/// ARPABET tokens, like `CORPUS  K AO1 R P AH0 S`. Lines starting with `;;;` are comments, and
+/// the number of alternative pronunciations (`CORPUS(1)`) is removed from the word.
+pub fn parse_cmudict(source: &str) -> Result<Vec<(String, PhonoString)>> {
+    let mut entries = vec![];
+    for line in source.lines() {
+        // inline comments, in recent versions of the dictionary
+        let line = line.split(" #").next().unwrap_or_default();
+        if line.trim().is_empty() || line.starts_with(";;;") {
+            continue;
+        }
+
+        let mut tokens = line.split_whitespace();
+        let Some(word) = tokens.next() else {
+            continue;
+        };
+        let tokens: Vec<&str> = tokens.collect();
+        if tokens.is_empty() {
+            return Err(Error::segment(
+                format!("no ARPABET symbol for \"{word}\""),
+                Some(Span::of(source, word)),
+            ));
+        }
+        if let Some(unknown) = tokens.iter().find(|t| arpabet_segments(t).is_none()) {
+            return Err(Error::segment(
+                format!("unknown ARPABET symbol \"{unknown}\""),
+                Some(Span::of(source, unknown)),
+            ));
+        }
+
+        let word = match word.strip_suffix(')').and_then(|w| w.rsplit_once('(')) {
+            Some((word, variant)) if variant.chars().all(|c| c.is_ascii_digit()) => word,
+            _ => word,
+        };
+        entries.push((word.to_string(), PhonoString::from_arpabet(&tokens)?));
+    }
+    Ok(entries)
+}
+
+/// Read a pronunciation dictionary file, see `parse_cmudict`
+pub fn load_cmudict<P: AsRef<Path>>(path: P) -> Result<Vec<(String, PhonoString)>> {
+    parse_cmudict(&fs::read_to_string(path)?)
+}
+
+/// the segments of an ARPABET token, with the stress of its syllable for vowels
+fn arpabet_segments(token: &str) -> Option<(Vec<SegmentFeatures>, Option<SyllableFeatures>)> {
+    if let Some((_, ipa)) = ARPABET_CONSONANTS.iter().find(|(t, _)| *t == token) {
+        return Some((ipa_segments(ipa), None));
+    }
+
+    let (symbol, stress) = match token.strip_suffix(['0', '1', '2']) {
+        Some(symbol) if token.ends_with('0') => (symbol, SyllableFeatures::UNSTRESSED),
+        Some(symbol) if token.ends_with('1') => (symbol, SyllableFeatures::PRIMARY_STRESS),
+        Some(symbol) => (symbol, SyllableFeatures::SECONDARY_STRESS),
+        None => (token, SyllableFeatures::new_undef()),
+    };
+    // the token with its digit first, like "AH0"
+    let (_, ipa) = ARPABET_VOWELS
+        .iter()
+        .find(|(t, _)| *t == token)
+        .or_else(|| ARPABET_VOWELS.iter().find(|(t, _)| *t == symbol))?;
+    Some((ipa_segments(ipa), Some(stress)))
+}
+
+/// the ARPABET token at the start of `segs`, with its number of segments, and whether it is a
+/// vowel. Takes the longest token, so diphthongs aren't split.
+fn arpabet_token(segs: &[&SegmentFeatures]) -> Option<(usize, &'static str, bool)> {
+    let vowels = ARPABET_VOWELS.iter().map(|(t, ipa)| (*t, *ipa, true));
+    let consonants = ARPABET_CONSONANTS.iter().map(|(t, ipa)| (*t, *ipa, false));
+    vowels
+        .chain(consonants)
+        .filter_map(|(token, ipa, is_vowel)| {
+            let token_segs = ipa_segments(ipa);
+            let matches =
+                token_segs.len() <= segs.len() && token_segs.iter().zip(segs).all(|(a, b)| a == *b);
+            // without the stress digit, added back from the syllable
+            matches.then(|| (token_segs.len(), token.trim_end_matches('0'), is_vowel))
+        })
+        .rev()
+        .max_by_key(|(len, _, _)| *len)
+}
+
+fn ipa_segments(ipa: &str) -> Vec<SegmentFeatures> {
+    let mut segs = vec![];
+    let mut rest = ipa;
+    while let Ok((rem, seg)) = parse_segment(rest) {
+        segs.push(seg);
+        rest = rem;
+    }
+    segs
+}
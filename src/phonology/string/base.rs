@@ -1,74 +1,320 @@
-use std::{fmt, ops::Range};
+use std::{fmt, ops::Range, str::FromStr, sync::Arc};
 
-use nom::{IResult, Parser};
+use nom::{IResult, error::ErrorKind};
 
 use crate::error::*;
 use crate::phonology::{
     feature::FeatureState,
-    rule::{compile_untagged_elements, parse_rule_elems},
-    segment::{SegmentFeatures, format_segment},
-    syllable::SyllableFeatures,
-    tree::Depth3Tree,
+    foot::{FOOT_BOUNDARY, FootFeatures, PHRASE_BOUNDARY},
+    rule::{compile_untagged_elements, parse_rule_elems_in},
+    segment::{Notation, SegmentFeatures, SegmentTable, format_segment_in},
+    syllable::{PRIMARY, STRESS, SyllableFeatures},
+    tree::{Attach, Depth3Tree, Depth5Tree},
 };
 
+/// The prosodic tree of a phonological string: phrases, words, feet, syllables and segments
+pub type ProsodicTree = Depth5Tree<(), (), FootFeatures, SyllableFeatures, SegmentFeatures>;
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct PhonoString {
-    pub tree: Depth3Tree<(), SyllableFeatures, SegmentFeatures>,
+    pub tree: ProsodicTree,
 }
 
 impl PhonoString {
+    /// A string of a single phrase from a tree of words, syllables and segments. Each word is a
+    /// single foot without features.
     pub fn new(tree: Depth3Tree<(), SyllableFeatures, SegmentFeatures>) -> Self {
+        Self { tree: tree.into() }
+    }
+
+    /// A string from its whole prosodic tree
+    pub fn from_tree(tree: ProsodicTree) -> Self {
         Self { tree }
     }
 
-    pub fn replace_range(mut self, range: Range<usize>, replace_with: PhonoString) -> Result<Self> {
-        self.tree = self.tree.replace_range(range, replace_with.tree)?;
+    /// Replace the segments in `range` with `replace_with`.
+    /// The syllables and feet cut by the range keep their features, unless the edge syllables
+    /// and feet of `replace_with` define them. If `replace_with` joins them into a single
+    /// syllable (or foot), it gets the features of both, those of the left one first, unless the
+    /// right syllable has a stronger stress.
+    pub fn replace_range(self, range: Range<usize>, replace_with: PhonoString) -> Result<Self> {
+        let (first, last) = (Some(range.start), range.end.checked_sub(1));
+        self.splice(first, last, replace_with, |tree, replace_with| {
+            tree.replace_range(range, replace_with)
+        })
+    }
+
+    /// Insert `insert` before the segment at `position` (or at the end). The inserted segments go
+    /// into the syllable on the `attach` side of the insertion point, which keeps its features
+    /// (and those of its foot) unless the first syllable of `insert` defines them. With
+    /// `Attach::Both`, `insert` replaces the boundary at `position`, and the syllables on both
+    /// sides keep their features like with `replace_range`.
+    pub fn insert(self, position: usize, attach: Attach, insert: PhonoString) -> Result<Self> {
+        // at the edges of the string, the segment on the other side
+        let before = position.checked_sub(1).filter(|i| *i < self.tree.len_4());
+        let after = Some(position).filter(|i| *i < self.tree.len_4());
+        let (left, right) = match attach {
+            Attach::Left => (before.or(after), before.or(after)),
+            Attach::Right => (after.or(before), after.or(before)),
+            Attach::Both => (before.or(after), after.or(before)),
+        };
+        self.splice(left, right, insert, |tree, insert| {
+            tree.insert_at(position, attach, insert)
+        })
+    }
+
+    /// Change the tree with `splice`, which puts `replace_with` in place of the syllables of the
+    /// segments `left` to `right`. The syllables and feet of these segments keep their features,
+    /// unless the edge syllables and feet of `replace_with` define them.
+    fn splice<F>(
+        mut self,
+        left: Option<usize>,
+        right: Option<usize>,
+        replace_with: PhonoString,
+        splice: F,
+    ) -> Result<Self>
+    where
+        F: FnOnce(ProsodicTree, ProsodicTree) -> Result<ProsodicTree>,
+    {
+        let left_syl = left.and_then(|i| self.syllable_of(i));
+        let right_syl = right.and_then(|i| self.syllable_of(i));
+        let left_foot = left_syl.as_ref().map(|(syl_idx, _)| self.foot_of(*syl_idx));
+        let right_foot = right_syl
+            .as_ref()
+            .map(|(syl_idx, _)| self.foot_of(*syl_idx));
+        let replaced_syl_n = replace_with.tree.len_3();
+        let replaced_foot_n = replace_with.tree.len_2();
+
+        self.tree = splice(self.tree, replace_with.tree)?;
+
+        if let (Some(left_syl), Some(right_syl)) = (left_syl, right_syl) {
+            let joined = replaced_syl_n == 1 && right_syl.0 != left_syl.0;
+            if joined && right_syl.1.stress_level() > left_syl.1.stress_level() {
+                // the joined syllable keeps the stronger stress
+                self.merge_syllable_features(left_syl.0, &right_syl.1);
+                self.merge_syllable_features(left_syl.0, &left_syl.1);
+            } else {
+                self.merge_syllable_features(left_syl.0, &left_syl.1);
+                if right_syl.0 != left_syl.0 {
+                    self.merge_syllable_features(left_syl.0 + replaced_syl_n - 1, &right_syl.1);
+                }
+            }
+        }
+        if let (Some(left_foot), Some(right_foot)) = (left_foot, right_foot) {
+            self.merge_foot_features(left_foot.0, &left_foot.1);
+            if right_foot.0 != left_foot.0 {
+                self.merge_foot_features(left_foot.0 + replaced_foot_n - 1, &right_foot.1);
+            }
+        }
         Ok(self)
     }
 
-    /// Parse a phonological string
+    /// Remove the syllables, feet, words and phrases left without segments, like after a
+    /// deletion. The features of the removed syllables are handled according to `policy`.
+    pub fn prune_empty(mut self, policy: EmptySyllablePolicy) -> Self {
+        let mut seg_counts = vec![0; self.tree.len_3()];
+        for (_, syl_idx) in self.tree.layer_4() {
+            seg_counts[*syl_idx] += 1;
+        }
+
+        for empty_idx in (0..seg_counts.len()).filter(|i| seg_counts[*i] == 0) {
+            let word_idx = self.word_of(empty_idx);
+            let in_word = |i: &usize| seg_counts[*i] > 0 && self.word_of(*i) == word_idx;
+            let mut previous = (0..empty_idx).rev().filter(in_word);
+            let mut next = (empty_idx + 1..seg_counts.len()).filter(in_word);
+            let neighbour = match policy {
+                EmptySyllablePolicy::Discard => None,
+                EmptySyllablePolicy::ToPrevious => previous.next().or_else(|| next.next()),
+                EmptySyllablePolicy::ToNext => next.next().or_else(|| previous.next()),
+            };
+
+            if let Some(neighbour) = neighbour {
+                let empty = self.tree.layer_3()[empty_idx].0.clone();
+                let syl = self.tree.get_depth_3_mut(neighbour);
+                // the whole stress, so a secondary stress stays secondary
+                if empty.stress_level() > syl.stress_level() {
+                    for feature in [STRESS, PRIMARY] {
+                        syl.features[feature as usize] = empty.features[feature as usize];
+                    }
+                }
+                if syl.tone.is_none() {
+                    syl.tone = empty.tone;
+                }
+            }
+        }
+
+        self.tree.prune();
+        self
+    }
+
+    /// Set the positive features dominated by negative nodes to `NA` in every segment, see
+    /// `SegmentFeatures::normalize`
+    pub fn normalize_features(mut self) -> Self {
+        for seg_idx in 0..self.tree.len_4() {
+            self.tree.get_depth_4_mut(seg_idx).normalize();
+        }
+        self
+    }
+
+    /// Fails on the first segment that contradicts the feature geometry, see
+    /// `SegmentFeatures::validate`
+    pub fn validate_features(&self) -> Result<()> {
+        self.tree
+            .layer_4()
+            .iter()
+            .try_for_each(|(seg, _)| seg.validate())
+    }
+
+    /// index and features of the syllable containing the segment at `seg_idx`
+    fn syllable_of(&self, seg_idx: usize) -> Option<(usize, SyllableFeatures)> {
+        let (_, syl_idx) = self.tree.layer_4().get(seg_idx)?;
+        let (syl, _) = &self.tree.layer_3()[*syl_idx];
+        Some((*syl_idx, syl.clone()))
+    }
+
+    /// index and features of the foot containing the syllable `syl_idx`
+    fn foot_of(&self, syl_idx: usize) -> (usize, FootFeatures) {
+        let foot_idx = self.tree.layer_3()[syl_idx].1;
+        (foot_idx, self.tree.layer_2()[foot_idx].0.clone())
+    }
+
+    /// index of the word containing the syllable `syl_idx`
+    pub(crate) fn word_of(&self, syl_idx: usize) -> usize {
+        let foot_idx = self.tree.layer_3()[syl_idx].1;
+        self.tree.layer_2()[foot_idx].1
+    }
+
+    /// fill the undefined features of syllable `syl_idx` with `features`
+    fn merge_syllable_features(&mut self, syl_idx: usize, features: &SyllableFeatures) {
+        let syl = self.tree.get_depth_3_mut(syl_idx);
+        *syl = features.clone() + syl.clone();
+    }
+
+    /// fill the undefined features of foot `foot_idx` with `features`
+    fn merge_foot_features(&mut self, foot_idx: usize, features: &FootFeatures) {
+        let foot = self.tree.get_depth_2_mut(foot_idx);
+        *foot = features.clone() + foot.clone();
+    }
+
+    /// Parse a phonological string in IPA. Segments within quotes are in X-SAMPA.
     pub fn parse(input: &str) -> IResult<&str, Self> {
-        let mut parser = parse_rule_elems;
+        Self::parse_in(input, Notation::Ipa)
+    }
 
-        let (remainder, elements) = parser.parse(input)?;
+    /// Parse a phonological string with the symbols of `notation`
+    pub fn parse_in(input: &str, notation: Notation) -> IResult<&str, Self> {
+        Self::parse_with(input, notation, &SegmentTable::current())
+    }
 
-        // TODO manage this error better
-        let string = compile_untagged_elements(elements).unwrap();
+    /// like `parse_in`, with the symbols and natural classes of `table` instead of the current
+    /// table
+    pub fn parse_with<'a>(
+        input: &'a str,
+        notation: Notation,
+        table: &Arc<SegmentTable>,
+    ) -> IResult<&'a str, Self> {
+        let (remainder, elements) =
+            table.scope(|| parse_rule_elems_in(input, notation, &table.classes))?;
+
+        let string = compile_untagged_elements(elements)
+            .map_err(|_| nom::Err::Failure(nom::error::Error::new(input, ErrorKind::Verify)))?;
 
         Ok((remainder, string))
     }
 
     pub fn format(&self) -> String {
+        self.format_in(Notation::Ipa)
+    }
+
+    /// like `format_in`, with the symbols of `table` instead of the current table
+    pub fn format_with(&self, notation: Notation, table: &Arc<SegmentTable>) -> String {
+        table.scope(|| self.format_in(notation))
+    }
+
+    /// Format the string with the symbols of `notation`
+    pub fn format_in(&self, notation: Notation) -> String {
         // TODO add format config options
         let mut output = "".to_string();
-        let mut is_first_word = true;
-        for (_, syls) in self.tree.iter() {
-            if is_first_word {
-                is_first_word = false;
-            } else {
-                output.push('#');
+        let layer_3 = self.tree.layer_3();
+        for (syl_idx, (syl, foot_idx)) in layer_3.iter().enumerate() {
+            let previous_foot = syl_idx.checked_sub(1).map(|i| layer_3[i].1);
+            let boundary = previous_foot
+                .filter(|previous| previous != foot_idx)
+                .map(|previous| self.boundary_between_feet(previous, *foot_idx));
+            if let Some(boundary) = boundary {
+                output.push_str(boundary);
             }
 
-            let mut is_first_syl = true;
-            for (syl, segs) in syls {
-                if is_first_syl {
-                    is_first_syl = false;
-                } else {
-                    if syl.features[0] == FeatureState::POS {
-                        output.push('\'');
-                    } else {
-                        output.push('.');
-                    }
+            // syllables start with their stress mark, or with a boundary if unstressed
+            if syl.features[STRESS as usize] != FeatureState::POS {
+                if syl_idx > 0 && previous_foot == Some(*foot_idx) {
+                    output.push('.');
                 }
+            } else if syl.features[PRIMARY as usize] == FeatureState::NEG {
+                output.push(notation.secondary_stress_mark());
+            } else {
+                output.push(notation.stress_mark());
+            }
 
-                for seg in segs {
-                    output.push_str(&format_segment(seg));
-                }
+            for (seg, _) in &self.tree.layer_4()[self.tree.children_4(syl_idx)] {
+                output.push_str(&format_segment_in(seg, notation));
+            }
+            if let Some(tone) = syl.tone {
+                output.push_str(&tone.letters());
             }
         }
 
         output
     }
+
+    /// the highest boundary between two neighbouring feet
+    fn boundary_between_feet(&self, left: usize, right: usize) -> &'static str {
+        let layer_2 = self.tree.layer_2();
+        let layer_1 = self.tree.layer_1();
+        let (left_word, right_word) = (layer_2[left].1, layer_2[right].1);
+        if left_word == right_word {
+            FOOT_BOUNDARY
+        } else if layer_1[left_word].1 == layer_1[right_word].1 {
+            "#"
+        } else {
+            PHRASE_BOUNDARY
+        }
+    }
+}
+
+impl FromStr for PhonoString {
+    type Err = Error;
+
+    /// Parse a whole phonological string, with an error pointing at the first invalid segment.
+    /// A string within `[...]` is in IPA, and a string within `"..."` is in X-SAMPA.
+    fn from_str(input: &str) -> Result<Self> {
+        match Notation::strip_delimiters(input) {
+            Some((notation, inner)) => parse_whole(inner, notation).or_else(|e| match notation {
+                // a bracketed feature set, like "[+syl]"
+                Notation::Ipa => parse_whole(input, Notation::Ipa).map_err(|_| e),
+                Notation::XSampa => Err(e),
+            }),
+            None => parse_whole(input, Notation::Ipa),
+        }
+    }
+}
+
+/// parse all of `input`, with an error pointing at the first invalid segment
+fn parse_whole(input: &str, notation: Notation) -> Result<PhonoString> {
+    let (rem, string) = PhonoString::parse_in(input, notation).map_err(|e| {
+        let span = match &e {
+            nom::Err::Error(e) | nom::Err::Failure(e) => Span::of(input, e.input),
+            nom::Err::Incomplete(_) => Span::new(input, 0..input.len()),
+        };
+        Error::segment("couldn't parse phonological string", Some(span))
+    })?;
+    if !rem.is_empty() {
+        return Err(Error::segment(
+            format!("unexpected \"{rem}\""),
+            Some(Span::of(input, rem)),
+        ));
+    }
+    Ok(string)
 }
 
 impl fmt::Display for PhonoString {
@@ -76,3 +322,17 @@ impl fmt::Display for PhonoString {
         write!(f, "{}", self.format())
     }
 }
+
+/// What happens to the features (like stress) of a syllable left without segments
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EmptySyllablePolicy {
+    /// the features are removed with the syllable
+    #[default]
+    Discard,
+    /// the stress goes to the previous syllable of the word, or the next one if there is none,
+    /// unless that syllable has a stronger stress. So does the tone, if that syllable has none.
+    ToPrevious,
+    /// the stress goes to the next syllable of the word, or the previous one if there is none,
+    /// unless that syllable has a stronger stress. So does the tone, if that syllable has none.
+    ToNext,
+}
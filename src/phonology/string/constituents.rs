@@ -0,0 +1,132 @@
+use std::{fmt, ops::Range};
+
+use nom::{
+    IResult, Parser,
+    branch::alt,
+    bytes::complete::tag,
+    combinator::value,
+    error::{Error, ErrorKind},
+    sequence::delimited,
+};
+
+use crate::phonology::{feature::FeatureState, segment::SYL, string::PhonoString};
+
+/// The part of a syllable a segment is in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Constituent {
+    Onset,
+    Nucleus,
+    Coda,
+}
+
+impl Constituent {
+    /// the name of the constituent in rules, like `coda` in `[+coda]`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Constituent::Onset => "onset",
+            Constituent::Nucleus => "nucleus",
+            Constituent::Coda => "coda",
+        }
+    }
+}
+
+impl fmt::Display for Constituent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The onset, nucleus and coda of a syllable, as ranges of segment indices in the string. The
+/// ranges follow each other, and any of them may be empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyllableConstituents {
+    pub onset: Range<usize>,
+    pub nucleus: Range<usize>,
+    pub coda: Range<usize>,
+}
+
+impl SyllableConstituents {
+    /// the constituent of the segment at `seg_idx`, None if it isn't in this syllable
+    pub fn of(&self, seg_idx: usize) -> Option<Constituent> {
+        if self.onset.contains(&seg_idx) {
+            Some(Constituent::Onset)
+        } else if self.nucleus.contains(&seg_idx) {
+            Some(Constituent::Nucleus)
+        } else if self.coda.contains(&seg_idx) {
+            Some(Constituent::Coda)
+        } else {
+            None
+        }
+    }
+
+    /// the nucleus and the coda
+    pub fn rhyme(&self) -> Range<usize> {
+        self.nucleus.start..self.coda.end
+    }
+}
+
+impl PhonoString {
+    /// The onset, nucleus and coda of the syllable `syl_idx`. The nucleus is the first run of
+    /// `[+syl]` segments, or the most sonorous segment (the first one on ties) in syllables
+    /// without syllabic segments. The segments before it are the onset, and the ones after it
+    /// are the coda.
+    pub fn constituents(&self, syl_idx: usize) -> SyllableConstituents {
+        let segs = self.tree.children_4(syl_idx);
+        let is_syllabic =
+            |i: &usize| self.tree.layer_4()[*i].0.features[SYL as usize] == FeatureState::POS;
+
+        let nucleus = match segs.clone().find(is_syllabic) {
+            Some(start) => {
+                let end = (start..segs.end).find(|i| !is_syllabic(i));
+                start..end.unwrap_or(segs.end)
+            }
+            // max_by_key takes the last maximum, so the first one of the syllable
+            None => match segs
+                .clone()
+                .rev()
+                .max_by_key(|i| self.tree.layer_4()[*i].0.sonority())
+            {
+                Some(peak) => peak..(peak + 1),
+                // an empty syllable
+                None => segs.clone(),
+            },
+        };
+
+        SyllableConstituents {
+            onset: segs.start..nucleus.start,
+            coda: nucleus.end..segs.end,
+            nucleus,
+        }
+    }
+
+    /// The onset, nucleus and coda of each syllable, see `PhonoString::constituents`
+    pub fn all_constituents(&self) -> Vec<SyllableConstituents> {
+        (0..self.tree.len_3())
+            .map(|syl_idx| self.constituents(syl_idx))
+            .collect()
+    }
+
+    /// The constituent of the segment at `seg_idx` in its syllable
+    pub fn constituent_of(&self, seg_idx: usize) -> Option<Constituent> {
+        let (_, syl_idx) = self.tree.layer_4().get(seg_idx)?;
+        self.constituents(*syl_idx).of(seg_idx)
+    }
+}
+
+/// Parse a syllable position in a rule, like `[+coda]` for segments in a coda, or `[-onset]` for
+/// segments outside of onsets. Returns the constituent and whether the segment is in it.
+pub fn parse_constituent(input: &str) -> IResult<&str, (Constituent, bool)> {
+    let sign = alt((value(true, tag("+")), value(false, tag("-"))));
+    let mut parser = delimited(tag("["), (sign, parse_constituent_name), tag("]"));
+
+    let (rest, (is_in, constituent)) = parser.parse(input)?;
+    Ok((rest, (constituent, is_in)))
+}
+
+fn parse_constituent_name(input: &str) -> IResult<&str, Constituent> {
+    [Constituent::Onset, Constituent::Nucleus, Constituent::Coda]
+        .into_iter()
+        .find(|c| input.starts_with(c.name()))
+        .map(|c| (&input[c.name().len()..], c))
+        .ok_or(nom::Err::Error(Error::new(input, ErrorKind::Tag)))
+}
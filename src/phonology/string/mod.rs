@@ -1,6 +1,16 @@
+mod arpabet;
 mod base;
+mod constituents;
+mod stress;
+mod syllabify;
+mod weight;
 
-pub use base::PhonoString;
+pub use arpabet::{load_cmudict, parse_cmudict};
+pub use base::{EmptySyllablePolicy, PhonoString};
+pub use constituents::*;
+pub use stress::*;
+pub use syllabify::*;
+pub use weight::*;
 
 #[cfg(test)]
 mod test;
@@ -0,0 +1,156 @@
+use std::fmt;
+
+use crate::error::*;
+use crate::phonology::{
+    string::{PhonoString, Weight, WeightOpts},
+    syllable::SyllableFeatures,
+};
+
+/// The edge of the word where footing starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    Left,
+    Right,
+}
+
+/// The stressed syllable of a foot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FootShape {
+    /// stress on the first syllable, like `ˈpa.ta`
+    Trochee,
+    /// stress on the second syllable, like `pa.ˈta`
+    Iamb,
+}
+
+/// A way of placing the stress of words (see `PhonoString::assign_stress`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StressSystem {
+    /// `initial` primary stress on the first syllable
+    Initial,
+    /// `final` primary stress on the last syllable
+    Final,
+    /// `penultimate` primary stress on the second to last syllable
+    Penultimate,
+    /// `latin` the Latin penultimate law: primary stress on the second to last syllable if it is
+    /// heavy, otherwise on the third to last one
+    Latin,
+    /// `trochaic ltr`, `iambic rtl`, ... feet of two syllables from an edge of the word, `ltr`
+    /// from the left. The foot at that edge has primary stress and the others secondary stress.
+    /// A syllable left over at the other edge stays unstressed, unless it is the whole word.
+    Feet(FootShape, Edge),
+}
+
+impl StressSystem {
+    /// Parse the name of a stress system, like `latin` or `trochaic rtl`
+    pub fn parse(input: &str) -> Result<Self> {
+        let words: Vec<&str> = input.split_whitespace().collect();
+        let system = match words.as_slice() {
+            ["initial"] => StressSystem::Initial,
+            ["final"] => StressSystem::Final,
+            ["penultimate"] => StressSystem::Penultimate,
+            ["latin"] => StressSystem::Latin,
+            [shape, edge] => {
+                let shape = match *shape {
+                    "trochaic" => FootShape::Trochee,
+                    "iambic" => FootShape::Iamb,
+                    _ => return Err(unknown_system(input)),
+                };
+                let edge = match *edge {
+                    "ltr" => Edge::Left,
+                    "rtl" => Edge::Right,
+                    _ => return Err(unknown_system(input)),
+                };
+                StressSystem::Feet(shape, edge)
+            }
+            _ => return Err(unknown_system(input)),
+        };
+        Ok(system)
+    }
+
+    /// The syllables of a word of `n` syllables with primary and secondary stress, as offsets
+    /// in the word. `weight` is the weight of the syllable at an offset.
+    fn stressed(&self, n: usize, weight: impl Fn(usize) -> Weight) -> (usize, Vec<usize>) {
+        let primary = match self {
+            StressSystem::Initial => 0,
+            StressSystem::Final => n - 1,
+            StressSystem::Penultimate => n.saturating_sub(2),
+            StressSystem::Latin if n >= 3 && weight(n - 2) == Weight::Light => n - 3,
+            StressSystem::Latin => n.saturating_sub(2),
+            StressSystem::Feet(_, _) if n == 1 => 0,
+            StressSystem::Feet(shape, edge) => {
+                // the stressed syllable of each foot, from the edge
+                let mut heads = (0..n / 2).map(|foot| {
+                    let first = match edge {
+                        Edge::Left => 2 * foot,
+                        Edge::Right => n - 2 * foot - 2,
+                    };
+                    match shape {
+                        FootShape::Trochee => first,
+                        FootShape::Iamb => first + 1,
+                    }
+                });
+                let primary = heads.next().unwrap_or(0);
+                return (primary, heads.collect());
+            }
+        };
+        (primary, vec![])
+    }
+}
+
+impl fmt::Display for StressSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StressSystem::Initial => write!(f, "initial"),
+            StressSystem::Final => write!(f, "final"),
+            StressSystem::Penultimate => write!(f, "penultimate"),
+            StressSystem::Latin => write!(f, "latin"),
+            StressSystem::Feet(shape, edge) => {
+                let shape = match shape {
+                    FootShape::Trochee => "trochaic",
+                    FootShape::Iamb => "iambic",
+                };
+                let edge = match edge {
+                    Edge::Left => "ltr",
+                    Edge::Right => "rtl",
+                };
+                write!(f, "{shape} {edge}")
+            }
+        }
+    }
+}
+
+fn unknown_system(input: &str) -> Error {
+    Error::rule_syntax(format!("unknown stress system \"{}\"", input.trim()), None)
+}
+
+impl PhonoString {
+    /// Place the stress of each word with `system`, replacing the stress already in the string.
+    /// Syllable weights (for `StressSystem::Latin`) are counted with `weight`. Tones are kept.
+    pub fn assign_stress(mut self, system: StressSystem, weight: &WeightOpts) -> Self {
+        let syl_n = self.tree.len_3();
+        let mut word_start = 0;
+        while word_start < syl_n {
+            let word = self.word_of(word_start);
+            let word_end = (word_start..syl_n)
+                .find(|syl_idx| self.word_of(*syl_idx) != word)
+                .unwrap_or(syl_n);
+
+            let (primary, secondary) = system.stressed(word_end - word_start, |offset| {
+                self.weight(word_start + offset, weight)
+            });
+            for offset in 0..(word_end - word_start) {
+                let stress = if offset == primary {
+                    SyllableFeatures::PRIMARY_STRESS
+                } else if secondary.contains(&offset) {
+                    SyllableFeatures::SECONDARY_STRESS
+                } else {
+                    SyllableFeatures::UNSTRESSED
+                };
+                self.tree.get_depth_3_mut(word_start + offset).features = stress.features;
+            }
+
+            word_start = word_end;
+        }
+        self
+    }
+}
@@ -0,0 +1,183 @@
+use std::ops::Range;
+
+use crate::error::*;
+use crate::phonology::{
+    feature::FeatureState,
+    segment::{SYL, SegmentFeatures},
+    string::PhonoString,
+    syllable::SyllableFeatures,
+    tree::Depth5Tree,
+};
+
+/// Options of the syllabifier
+#[derive(Debug, Clone, Default)]
+pub struct SyllabifyOpts {
+    /// keep the syllable boundaries already in the string, and only split its syllables.
+    /// Otherwise the syllables of each word are rebuilt from scratch.
+    pub keep_boundaries: bool,
+    /// legal onsets, each one a sequence of segments or natural classes. When `None`, an onset
+    /// is legal if its sonority rises towards the nucleus.
+    pub onsets: Option<Vec<Vec<SegmentFeatures>>>,
+    /// legal codas, like `onsets`. When `None`, any coda is legal.
+    pub codas: Option<Vec<Vec<SegmentFeatures>>>,
+}
+
+impl SyllabifyOpts {
+    /// Parse clusters for `onsets` or `codas`, like `["pl", "tr", "sC"]`
+    pub fn parse_clusters(clusters: &[&str]) -> Result<Vec<Vec<SegmentFeatures>>> {
+        clusters
+            .iter()
+            .map(|cluster| {
+                let string: PhonoString = cluster.parse()?;
+                Ok(string
+                    .tree
+                    .layer_4()
+                    .iter()
+                    .map(|(seg, _)| seg.clone())
+                    .collect())
+            })
+            .collect()
+    }
+
+    fn is_legal_onset(&self, cluster: &[SegmentFeatures]) -> bool {
+        match &self.onsets {
+            _ if cluster.is_empty() => true,
+            Some(onsets) => is_listed(cluster, onsets),
+            None => cluster
+                .windows(2)
+                .all(|pair| pair[0].sonority() < pair[1].sonority()),
+        }
+    }
+
+    fn is_legal_coda(&self, cluster: &[SegmentFeatures]) -> bool {
+        match &self.codas {
+            _ if cluster.is_empty() => true,
+            Some(codas) => is_listed(cluster, codas),
+            None => true,
+        }
+    }
+}
+
+impl PhonoString {
+    /// Rebuild the syllables of each word with the Maximal Onset Principle: each syllabic
+    /// segment is a nucleus, and the consonants between two nuclei go to the onset of the second
+    /// one, as long as the onset is legal. The rest goes to the coda of the first one.
+    ///
+    /// A new syllable gets the features (like stress) of the old syllable of its nucleus, and
+    /// goes into the foot of that syllable. When an old syllable is split, only the first
+    /// syllable with a nucleus from it keeps its features.
+    pub fn syllabify(self, opts: &SyllabifyOpts) -> Self {
+        let segs = self.tree.layer_4();
+        let syls = self.tree.layer_3();
+        let feet = self.tree.layer_2();
+        let words = self.tree.layer_1();
+
+        let mut tree = Depth5Tree::new();
+        let mut last_phrase = None;
+        for (word_idx, (_, phrase_idx)) in words.iter().enumerate() {
+            let word_segs: Vec<usize> = (0..segs.len())
+                .filter(|i| self.word_of(segs[*i].1) == word_idx)
+                .collect();
+            if word_segs.is_empty() {
+                continue;
+            }
+            if last_phrase != Some(*phrase_idx) {
+                tree.push_depth_0(());
+                last_phrase = Some(*phrase_idx);
+            }
+            tree.push_depth_1(());
+            let mut last_foot = None;
+
+            // segments that can't be in the same syllable, for each existing syllable if they
+            // are kept
+            let chunks: Vec<&[usize]> = if opts.keep_boundaries {
+                word_segs
+                    .chunk_by(|a, b| segs[*a].1 == segs[*b].1)
+                    .collect()
+            } else {
+                vec![&word_segs]
+            };
+
+            let mut featured_syls = vec![];
+            for chunk in chunks {
+                let features: Vec<SegmentFeatures> =
+                    chunk.iter().map(|i| segs[*i].0.clone()).collect();
+                for syl_segs in split_syllables(&features, opts) {
+                    let seg_indices = &chunk[syl_segs.clone()];
+                    let nucleus = syl_segs
+                        .clone()
+                        .find(|i| features[*i].features[SYL as usize] == FeatureState::POS)
+                        .unwrap_or(syl_segs.start);
+                    let old_syl = segs[chunk[nucleus]].1;
+                    let foot_idx = syls[old_syl].1;
+                    if last_foot != Some(foot_idx) {
+                        tree.push_depth_2(feet[foot_idx].0.clone());
+                        last_foot = Some(foot_idx);
+                    }
+
+                    let syl_features = if featured_syls.contains(&old_syl) {
+                        SyllableFeatures::new_undef()
+                    } else {
+                        featured_syls.push(old_syl);
+                        syls[old_syl].0.clone()
+                    };
+                    tree.push_depth_3(syl_features);
+                    for seg_idx in seg_indices {
+                        tree.push_depth_4(segs[*seg_idx].0.clone());
+                    }
+                }
+            }
+        }
+
+        Self { tree }
+    }
+}
+
+/// Split a sequence of segments into syllables, returns the range of segments of each syllable
+fn split_syllables(segs: &[SegmentFeatures], opts: &SyllabifyOpts) -> Vec<Range<usize>> {
+    let nuclei: Vec<Range<usize>> = (0..segs.len())
+        .filter(|i| segs[*i].features[SYL as usize] == FeatureState::POS)
+        .map(|i| i..(i + 1))
+        .collect();
+    split_syllables_at(segs, &nuclei, opts)
+}
+
+/// Split a sequence of segments into syllables around the given nuclei, in order, returns the
+/// range of segments of each syllable. A nucleus can have several segments, like a diphthong.
+pub(crate) fn split_syllables_at(
+    segs: &[SegmentFeatures],
+    nuclei: &[Range<usize>],
+    opts: &SyllabifyOpts,
+) -> Vec<Range<usize>> {
+    if segs.is_empty() {
+        return vec![];
+    }
+
+    // first segment of each syllable. consonants before the first nucleus are its onset, and
+    // consonants after the last nucleus are its coda. without nuclei, there is one syllable.
+    let mut starts = vec![0];
+    for pair in nuclei.windows(2) {
+        let cluster = &segs[pair[0].end..pair[1].start];
+        // coda length, the onset is the rest of the cluster
+        let split = (0..=cluster.len())
+            .find(|s| opts.is_legal_onset(&cluster[*s..]) && opts.is_legal_coda(&cluster[..*s]))
+            .or_else(|| (0..=cluster.len()).find(|s| opts.is_legal_onset(&cluster[*s..])))
+            .unwrap_or(cluster.len()); // an empty onset is always legal
+        starts.push(pair[0].end + split);
+    }
+
+    let mut ends = starts[1..].to_vec();
+    ends.push(segs.len());
+    starts.into_iter().zip(ends).map(|(s, e)| s..e).collect()
+}
+
+/// true if `cluster` matches one of the `listed` clusters, segment by segment
+fn is_listed(cluster: &[SegmentFeatures], listed: &[Vec<SegmentFeatures>]) -> bool {
+    listed.iter().any(|pattern| {
+        pattern.len() == cluster.len()
+            && cluster
+                .iter()
+                .zip(pattern)
+                .all(|(seg, pattern_seg)| seg.matches(pattern_seg))
+    })
+}
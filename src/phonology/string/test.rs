@@ -1,23 +1,31 @@
 use crate::d3tree;
+use crate::error::Error;
 use crate::phonology::feature::FeatureState::*;
-use crate::phonology::syllable::SyllableFeatures;
-use crate::phonology::{segment::SegmentFeatures, string::PhonoString};
+use crate::phonology::foot::FootFeatures;
+use crate::phonology::syllable::{STRESS, SyllableFeatures, Tone};
+use crate::phonology::{
+    segment::{Notation, SegmentFeatures},
+    string::{
+        Constituent, Edge, EmptySyllablePolicy, FootShape, PhonoString, StressSystem,
+        SyllabifyOpts, SyllableConstituents, Weight, WeightOpts, parse_cmudict,
+    },
+};
 
 const A_SEG: SegmentFeatures = SegmentFeatures::from_features([
     POS, NEG, NEG, POS, POS, NEG, POS, NEG, NEG, NEG, POS, NEG, NEG, NEG, NEG, NEG, NEG, NA, NA,
-    NA, NEG, POS, NEG, POS, NEG, NEG, NA,
+    NA, NEG, POS, NEG, POS, NEG, NEG, NA, NEG,
 ]);
 const K_SEG: SegmentFeatures = SegmentFeatures::from_features([
     NEG, NEG, POS, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NEG, NA, NA,
-    NA, NEG, POS, POS, NEG, NA, NA, NA,
+    NA, NEG, POS, POS, NEG, NA, NA, NA, NEG,
 ]);
 const I_SEG: SegmentFeatures = SegmentFeatures::from_features([
     POS, NEG, NEG, POS, POS, NEG, POS, NEG, NEG, NEG, POS, NEG, NEG, NEG, NEG, NEG, NEG, NA, NA,
-    NA, NEG, POS, POS, NEG, POS, NEG, POS,
+    NA, NEG, POS, POS, NEG, POS, NEG, POS, NEG,
 ]);
 
-const UNSTRESSED: SyllableFeatures = SyllableFeatures::new([NEG]);
-const STRESSED: SyllableFeatures = SyllableFeatures::new([POS]);
+const UNSTRESSED: SyllableFeatures = SyllableFeatures::UNSTRESSED;
+const STRESSED: SyllableFeatures = SyllableFeatures::PRIMARY_STRESS;
 
 #[test]
 fn string_replace_in_syl() {
@@ -114,11 +122,389 @@ fn test_parse_stress() {
     let (rem, string) = PhonoString::parse("'ka.ta").unwrap();
     assert_eq!(rem, "");
 
-    let syls: Vec<&SyllableFeatures> = string.tree.iter()
-        .flat_map(|(_, syls)| syls.map(|(syl, _)| syl))
-        .collect();
+    let syls: Vec<&SyllableFeatures> = string.tree.layer_3().iter().map(|(syl, _)| syl).collect();
 
     assert_eq!(syls.len(), 2);
     assert_eq!(*syls[0], STRESSED);
-    assert_ne!(syls[1].features[0], POS);
+    assert_ne!(syls[1].features[STRESS as usize], POS);
+}
+
+#[test]
+fn test_parse_secondary_stress() {
+    let string: PhonoString = "ˌkɔɹ.pəˈsɛ.tə".parse().unwrap();
+    let syls: Vec<SyllableFeatures> = string
+        .tree
+        .layer_3()
+        .iter()
+        .map(|(syl, _)| syl.clone())
+        .collect();
+    assert_eq!(syls[0], SyllableFeatures::SECONDARY_STRESS);
+    assert_eq!(syls[1], SyllableFeatures::new_undef());
+    assert_eq!(syls[2], SyllableFeatures::PRIMARY_STRESS);
+
+    // ASCII and X-SAMPA marks, with the stress of the first syllable kept when formatting
+    assert_eq!(string, ",kɔɹ.pə'sɛ.tə".parse().unwrap());
+    let (_, xsampa) = PhonoString::parse_in("%kOr\\.p@\"sE.t@", Notation::XSampa).unwrap();
+    assert_eq!(string, xsampa);
+    assert_eq!(string.format(), "ˌkɔɹ.pəˈsɛ.tə");
+    assert_eq!(string.format_in(Notation::XSampa), "%kOr\\.p@\"sE.t@");
+
+    let string =
+        PhonoString::from_arpabet(&["K", "AO2", "R", "P", "AH0", "S", "EH1", "T", "AH0"]).unwrap();
+    assert_eq!(string.format(), "ˌkɔɹ.pəˈsɛ.tə");
+}
+
+#[test]
+fn test_constituents() {
+    let string: PhonoString = "strank.kai.pst.ˈn̩".parse().unwrap();
+    let constituents = string.all_constituents();
+    assert_eq!(
+        constituents[0],
+        SyllableConstituents {
+            onset: 0..3,
+            nucleus: 3..4,
+            coda: 4..6
+        }
+    );
+    // a run of syllabic segments is a single nucleus
+    assert_eq!(constituents[1].nucleus, 7..9);
+    assert!(constituents[1].coda.is_empty());
+    // without syllabic segments, the most sonorous one
+    assert_eq!(constituents[2].nucleus, 10..11);
+    assert_eq!(constituents[3].nucleus, 12..13);
+    assert!(constituents[3].onset.is_empty());
+
+    assert_eq!(string.constituent_of(0), Some(Constituent::Onset));
+    assert_eq!(string.constituent_of(5), Some(Constituent::Coda));
+    assert_eq!(string.constituent_of(8), Some(Constituent::Nucleus));
+    assert_eq!(string.constituent_of(13), None);
+}
+
+#[test]
+fn test_weight() {
+    let string: PhonoString = "ta.taː.tan.taːn.tai".parse().unwrap();
+    let opts = WeightOpts::default();
+    let morae: Vec<usize> = (0..5).map(|syl| string.morae(syl, &opts)).collect();
+    assert_eq!(morae, [1, 2, 2, 3, 2]);
+    assert_eq!(string.weight(0, &opts), Weight::Light);
+    assert_eq!(string.weight(2, &opts), Weight::Heavy);
+    assert_eq!(string.weight(3, &opts), Weight::Superheavy);
+
+    // without moraic codas, only long vowels and diphthongs are heavy
+    let opts = WeightOpts {
+        moraic_codas: false,
+    };
+    assert_eq!(string.weight(2, &opts), Weight::Light);
+    assert_eq!(string.weight(3, &opts), Weight::Heavy);
+    assert_eq!(string.weight(4, &opts), Weight::Heavy);
+}
+
+/// assign stress to `input` with `system`, and format it
+fn stressed(input: &str, system: StressSystem) -> String {
+    let string: PhonoString = input.parse().unwrap();
+    string
+        .assign_stress(system, &WeightOpts::default())
+        .format()
+}
+
+#[test]
+fn test_assign_stress() {
+    assert_eq!(stressed("ta.ta.ta", StressSystem::Initial), "ˈta.ta.ta");
+    assert_eq!(stressed("ta.ta.ta", StressSystem::Final), "ta.taˈta");
+    assert_eq!(
+        stressed("ta.ta.ta#ta", StressSystem::Penultimate),
+        "taˈta.ta#ˈta"
+    );
+    // hand-marked stress is replaced
+    assert_eq!(stressed("ˈta.ta", StressSystem::Final), "taˈta");
+}
+
+#[test]
+fn test_assign_latin_stress() {
+    let latin = StressSystem::Latin;
+    assert_eq!(stressed("kor.pus", latin), "ˈkor.pus");
+    assert_eq!(stressed("dik.taː.re", latin), "dikˈtaː.re");
+    assert_eq!(stressed("for.tis.si.mus", latin), "forˈtis.si.mus");
+    assert_eq!(stressed("do.mi.nus", latin), "ˈdo.mi.nus");
+    assert_eq!(stressed("ma.ɡis.ter", latin), "maˈɡis.ter");
+
+    // without moraic codas, closed syllables are light
+    let string: PhonoString = "ma.ɡis.ter".parse().unwrap();
+    let opts = WeightOpts {
+        moraic_codas: false,
+    };
+    assert_eq!(string.assign_stress(latin, &opts).format(), "ˈma.ɡis.ter");
+}
+
+#[test]
+fn test_assign_foot_stress() {
+    let feet = |shape, edge| StressSystem::Feet(shape, edge);
+    let input = "pa.ta.ka.ma.la";
+    assert_eq!(
+        stressed(input, feet(FootShape::Trochee, Edge::Left)),
+        "ˈpa.taˌka.ma.la"
+    );
+    assert_eq!(
+        stressed(input, feet(FootShape::Trochee, Edge::Right)),
+        "paˌta.kaˈma.la"
+    );
+    assert_eq!(
+        stressed(input, feet(FootShape::Iamb, Edge::Left)),
+        "paˈta.kaˌma.la"
+    );
+    assert_eq!(
+        stressed(input, feet(FootShape::Iamb, Edge::Right)),
+        "pa.taˌka.maˈla"
+    );
+    assert_eq!(stressed("pa", feet(FootShape::Iamb, Edge::Left)), "ˈpa");
+
+    assert_eq!(
+        StressSystem::parse("iambic rtl").unwrap(),
+        feet(FootShape::Iamb, Edge::Right)
+    );
+    assert!(StressSystem::parse("iambic").is_err());
+}
+
+#[test]
+fn test_parse_feet_and_phrases() {
+    let string: PhonoString = "ˈpa.ta|ˌka#Σ[-trochaic]ma.ˈla‖to".parse().unwrap();
+    assert_eq!(string.tree.len_0(), 2);
+    assert_eq!(string.tree.len_1(), 3);
+    assert_eq!(string.tree.len_2(), 4);
+    assert_eq!(string.tree.len_3(), 6);
+    assert_eq!(string.tree.layer_2()[2].0, FootFeatures::new([NEG]));
+
+    // foot features aren't formatted
+    assert_eq!(string.format(), "ˈpa.ta|ˌka#maˈla‖to");
+    assert_eq!(string, "ˈpa.ta|ˌka#Σ[-trochaic]ma.ˈla||to".parse().unwrap());
+
+    // `|\` is a click in X-SAMPA, not a foot boundary
+    let (_, click) = PhonoString::parse_in("|\\a|a", Notation::XSampa).unwrap();
+    assert_eq!(click.tree.len_2(), 2);
+    assert_eq!(click.format(), "ǀa|a");
+}
+
+#[test]
+fn test_parse_tone() {
+    let tones = |input: &str| -> Vec<Option<Tone>> {
+        let string: PhonoString = input.parse().unwrap();
+        string
+            .tree
+            .layer_3()
+            .iter()
+            .map(|(syl, _)| syl.tone)
+            .collect()
+    };
+    let falling = Tone::new(&[5, 1]).unwrap();
+    let dipping = Tone::new(&[2, 1, 4]).unwrap();
+
+    // Chao letters, superscript numbers and diacritics, precomposed or not
+    assert_eq!(tones("ma˥˩.ma˨˩˦"), [Some(falling), Some(dipping)]);
+    assert_eq!(tones("ma⁵¹.ma²¹⁴"), [Some(falling), Some(dipping)]);
+    assert_eq!(tones("mâ.ma"), [Some(falling), None]);
+    assert_eq!(tones("ma\u{0302}n.mǎ"), tones("mân.ma˩˥"));
+    assert_eq!(tones("má"), [Some(Tone::level(4))]);
+    // repeated levels are a level tone
+    assert_eq!(tones("ma˥˥"), [Some(Tone::level(5))]);
+
+    let string: PhonoString = "táːn.mà".parse().unwrap();
+    assert_eq!(string.format(), "taːn˦.ma˨");
+    assert_eq!(dipping.numbers(), "²¹⁴");
+
+    // a tone needs a syllable
+    assert!("˥ma".parse::<PhonoString>().is_err());
+    assert!("ma.˥".parse::<PhonoString>().is_err());
+    assert!("ma˥˦˧˨".parse::<PhonoString>().is_err());
+}
+
+#[test]
+fn string_prune_empty() {
+    // [ka.'.i#] with an empty stressed syllable and an empty word
+    let string = PhonoString::new(d3tree![
+        () => [
+            UNSTRESSED => [K_SEG, A_SEG],
+            STRESSED => [],
+            UNSTRESSED => [I_SEG],
+        ],
+        () => [UNSTRESSED => []]
+    ]);
+
+    let discarded = string.clone().prune_empty(EmptySyllablePolicy::Discard);
+    let expected = PhonoString::new(d3tree![
+        () => [
+            UNSTRESSED => [K_SEG, A_SEG],
+            UNSTRESSED => [I_SEG],
+        ]
+    ]);
+    assert_eq!(discarded, expected);
+    assert!(discarded.tree.test_invariants());
+    assert!(discarded.tree.are_leaves_depth_5());
+
+    let to_previous = string.clone().prune_empty(EmptySyllablePolicy::ToPrevious);
+    let expected = PhonoString::new(d3tree![
+        () => [
+            STRESSED => [K_SEG, A_SEG],
+            UNSTRESSED => [I_SEG],
+        ]
+    ]);
+    assert_eq!(to_previous, expected);
+
+    let to_next = string.prune_empty(EmptySyllablePolicy::ToNext);
+    let expected = PhonoString::new(d3tree![
+        () => [
+            UNSTRESSED => [K_SEG, A_SEG],
+            STRESSED => [I_SEG],
+        ]
+    ]);
+    assert_eq!(to_next, expected);
+}
+
+#[test]
+fn test_parse_error_span() {
+    let err = "ka.t%a".parse::<PhonoString>().err().unwrap();
+    assert!(matches!(err, Error::Segment(_)), "{err}");
+
+    let span = err.span().unwrap();
+    assert_eq!((span.line, span.column), (1, 5));
+    assert_eq!(span.range, 4..6);
+
+    assert!("ka.ta".parse::<PhonoString>().is_ok());
+}
+
+/// Macro for generating syllabification tests
+macro_rules! gen_test_syllabify {
+    ($name:ident, $opts:expr, $input:expr, $expected:expr) => {
+        paste::paste! {
+            #[test]
+            fn [<test_syllabify_ $name>]() {
+                let string: PhonoString = $input.parse().unwrap();
+                let expected: PhonoString = $expected.parse().unwrap();
+                let actual = string.syllabify(&$opts);
+                if actual != expected {
+                    panic!("expected=[{expected}] != actual=[{actual}]");
+                }
+                assert!(actual.tree.test_invariants());
+                assert!(actual.tree.are_leaves_depth_5());
+            }
+        }
+    };
+}
+
+gen_test_syllabify!(open, SyllabifyOpts::default(), "kata", "ka.ta");
+gen_test_syllabify!(rising_onset, SyllabifyOpts::default(), "apla", "a.pla");
+gen_test_syllabify!(falling_cluster, SyllabifyOpts::default(), "kasta", "kas.ta");
+gen_test_syllabify!(edges, SyllabifyOpts::default(), "skamp#a", "skamp#a");
+gen_test_syllabify!(hiatus, SyllabifyOpts::default(), "kaio", "ka.i.o");
+gen_test_syllabify!(
+    listed_onsets,
+    SyllabifyOpts {
+        onsets: Some(SyllabifyOpts::parse_clusters(&["st", "C"]).unwrap()),
+        ..Default::default()
+    },
+    "kasta",
+    "ka.sta"
+);
+gen_test_syllabify!(
+    listed_codas,
+    SyllabifyOpts {
+        codas: Some(SyllabifyOpts::parse_clusters(&["mp"]).unwrap()),
+        ..Default::default()
+    },
+    "ampla",
+    "amp.la"
+);
+gen_test_syllabify!(
+    drop_boundaries,
+    SyllabifyOpts::default(),
+    "kat.ata",
+    "ka.ta.ta"
+);
+gen_test_syllabify!(
+    keep_boundaries,
+    SyllabifyOpts {
+        keep_boundaries: true,
+        ..Default::default()
+    },
+    "kat.ata",
+    "kat.a.ta"
+);
+gen_test_syllabify!(
+    stress_follows_nucleus,
+    SyllabifyOpts::default(),
+    "ka'tka",
+    "kat'ka"
+);
+gen_test_syllabify!(stress_split, SyllabifyOpts::default(), "'akta", "'ak.ta");
+
+#[test]
+fn test_parse_xsampa() {
+    let (rem, string) = PhonoString::parse_in("\"tSa.k{~", Notation::XSampa).unwrap();
+    assert_eq!(rem, "");
+    assert_eq!(string, "'t͡ʃa.kæ̃".parse().unwrap());
+    assert_eq!(string.format_in(Notation::XSampa), "\"tSa.k{~");
+}
+
+#[test]
+fn test_parse_delimited_notation() {
+    let ipa: PhonoString = "[ka'ʃa]".parse().unwrap();
+    let xsampa: PhonoString = "\"ka\"Sa\"".parse().unwrap();
+    assert_eq!(ipa, xsampa);
+
+    // a bracketed feature set isn't an IPA string
+    let features: PhonoString = "[+syl]".parse().unwrap();
+    assert_eq!(features.tree.len_4(), 1);
+}
+
+#[test]
+fn test_from_arpabet() {
+    let tokens = ["K", "AO1", "R", "P", "AH0", "S"];
+    let string = PhonoString::from_arpabet(&tokens).unwrap();
+    assert_eq!(string.format(), "ˈkɔɹ.pəs");
+
+    let stress: Vec<_> = string
+        .tree
+        .layer_3()
+        .iter()
+        .map(|(syl, _)| syl.clone())
+        .collect();
+    assert_eq!(
+        stress,
+        vec![
+            SyllableFeatures::PRIMARY_STRESS,
+            SyllableFeatures::UNSTRESSED
+        ]
+    );
+    assert_eq!(string.to_arpabet().unwrap(), tokens);
+}
+
+#[test]
+fn test_arpabet_diphthong() {
+    let string = PhonoString::from_arpabet(&["T", "AY1", "D", "IY0"]).unwrap();
+    assert_eq!(string.format(), "ˈtaɪ.di");
+    assert_eq!(string.to_arpabet().unwrap(), ["T", "AY1", "D", "IY0"]);
+}
+
+#[test]
+fn test_parse_cmudict() {
+    let source = ";;; comment\nCORPUS  K AO1 R P AH0 S\nTIDY(1)  T AY1 D IY0\n";
+    let entries = parse_cmudict(source).unwrap();
+    let words: Vec<&str> = entries.iter().map(|(word, _)| word.as_str()).collect();
+    assert_eq!(words, ["CORPUS", "TIDY"]);
+    assert_eq!(entries[1].1.format(), "ˈtaɪ.di");
+
+    let Err(Error::Segment(message)) = parse_cmudict("CORPUS  K AO1 R P AH0 SS\n") else {
+        panic!("expected a segment error");
+    };
+    assert_eq!(message.span.unwrap().range, 22..24);
+
+    let Err(Error::Segment(message)) = parse_cmudict("CORPUS  K AO1\nA\n") else {
+        panic!("expected a segment error");
+    };
+    assert_eq!(message.span.unwrap().range, 14..15);
+}
+
+#[test]
+fn test_from_arpabet_empty() {
+    let tokens: [&str; 0] = [];
+    assert!(PhonoString::from_arpabet(&tokens).is_err());
 }
@@ -0,0 +1,119 @@
+use std::ops::RangeInclusive;
+
+use nom::{
+    IResult, Parser,
+    branch::alt,
+    bytes::complete::tag,
+    combinator::value,
+    error::{Error, ErrorKind},
+};
+
+use crate::phonology::{
+    feature::FeatureState,
+    segment::LONG,
+    string::{PhonoString, SyllableConstituents},
+};
+
+/// Options of mora counting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeightOpts {
+    /// each coda segment is a mora, like in Latin where `CVC` syllables are heavy. Otherwise only
+    /// the nucleus counts.
+    pub moraic_codas: bool,
+}
+
+impl Default for WeightOpts {
+    fn default() -> Self {
+        Self { moraic_codas: true }
+    }
+}
+
+/// The weight of a syllable, from its number of morae
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Weight {
+    /// one mora or less, like `CV`
+    Light,
+    /// two morae, like `CVː` or `CVC` with moraic codas
+    Heavy,
+    /// three morae or more, like `CVːC` with moraic codas
+    Superheavy,
+}
+
+impl Weight {
+    pub fn of_morae(morae: usize) -> Self {
+        match morae {
+            0 | 1 => Weight::Light,
+            2 => Weight::Heavy,
+            _ => Weight::Superheavy,
+        }
+    }
+
+    /// the name of the weight class in rules, like `heavy` in `σ[+heavy]`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Weight::Light => "light",
+            Weight::Heavy => "heavy",
+            Weight::Superheavy => "superheavy",
+        }
+    }
+
+    /// The weights in the class of this weight, or out of it if `is_in` is false. `heavy`
+    /// syllables are heavy or superheavy, so `-heavy` syllables are light.
+    pub fn class(self, is_in: bool) -> RangeInclusive<Weight> {
+        match (self, is_in) {
+            (Weight::Light, true) => Weight::Light..=Weight::Light,
+            (Weight::Light, false) => Weight::Heavy..=Weight::Superheavy,
+            (weight, true) => weight..=Weight::Superheavy,
+            (Weight::Heavy, false) => Weight::Light..=Weight::Light,
+            (Weight::Superheavy, false) => Weight::Light..=Weight::Heavy,
+        }
+    }
+}
+
+impl PhonoString {
+    /// The number of morae of the syllable `syl_idx`: one for each segment of the nucleus, or
+    /// two if it is `[+long]`, and one for each segment of the coda if codas are moraic (see
+    /// `WeightOpts`). The onset doesn't count.
+    pub fn morae(&self, syl_idx: usize, opts: &WeightOpts) -> usize {
+        let SyllableConstituents { nucleus, coda, .. } = self.constituents(syl_idx);
+        let segs = self.tree.layer_4();
+        let nucleus_morae: usize = segs[nucleus]
+            .iter()
+            .map(|(seg, _)| match seg.features[LONG as usize] {
+                FeatureState::POS => 2,
+                _ => 1,
+            })
+            .sum();
+        let coda_morae = if opts.moraic_codas { coda.len() } else { 0 };
+        nucleus_morae + coda_morae
+    }
+
+    /// The weight of the syllable `syl_idx`, from its morae (see `PhonoString::morae`)
+    pub fn weight(&self, syl_idx: usize, opts: &WeightOpts) -> Weight {
+        Weight::of_morae(self.morae(syl_idx, opts))
+    }
+}
+
+/// The weights in both classes `a` and `b`, like `Heavy..=Heavy` for `+heavy` and `-superheavy`
+pub fn weight_class_intersection(
+    a: RangeInclusive<Weight>,
+    b: RangeInclusive<Weight>,
+) -> RangeInclusive<Weight> {
+    (*a.start()).max(*b.start())..=(*a.end()).min(*b.end())
+}
+
+/// Parse a weight class in a syllable feature set, like `+heavy` for heavy or superheavy
+/// syllables, or `-superheavy` for the others. Returns the weights of the class.
+pub fn parse_weight_class(input: &str) -> IResult<&str, RangeInclusive<Weight>> {
+    let sign = alt((value(true, tag("+")), value(false, tag("-"))));
+    let (rest, (is_in, weight)) = (sign, parse_weight_name).parse(input)?;
+    Ok((rest, weight.class(is_in)))
+}
+
+fn parse_weight_name(input: &str) -> IResult<&str, Weight> {
+    [Weight::Light, Weight::Heavy, Weight::Superheavy]
+        .into_iter()
+        .find(|w| input.starts_with(w.name()))
+        .map(|w| (&input[w.name().len()..], w))
+        .ok_or(nom::Err::Error(Error::new(input, ErrorKind::Tag)))
+}
@@ -1,36 +1,74 @@
-use crate::phonology::feature::FeatureState::{self, *};
-use crate::phonology::syllable::SYL_FEATURE_COUNT;
+use crate::phonology::feature::{
+    FeatureState::{self, *},
+    VariableBindings,
+};
+use crate::phonology::syllable::{PRIMARY, STRESS, SYL_FEATURE_COUNT, Tone};
 use std::ops::Add;
 
+/// The symbol of syllables in rules, before a syllable feature set: `σ[+heavy]`
+pub const SYLLABLE_SYMBOL: char = 'σ';
+
 /// set of features a syllable can have
 #[derive(Debug, Clone, PartialEq)]
 pub struct SyllableFeatures {
     // TODO see if this can be private
     pub features: [FeatureState; SYL_FEATURE_COUNT as usize],
+    /// the syllable's tone, if it has one (or, in patterns, if it must have one)
+    pub tone: Option<Tone>,
 }
 
 impl SyllableFeatures {
+    /// a syllable with primary stress, like after `ˈ`
+    pub const PRIMARY_STRESS: Self = Self::new([POS, POS]);
+    /// a syllable with secondary stress, like after `ˌ`
+    pub const SECONDARY_STRESS: Self = Self::new([POS, NEG]);
+    /// an unstressed syllable
+    pub const UNSTRESSED: Self = Self::new([NEG, NA]);
+
     pub const fn new(features: [FeatureState; SYL_FEATURE_COUNT as usize]) -> Self {
-        Self { features }
+        Self {
+            features,
+            tone: None,
+        }
     }
 
     /// construct a syllable feature set with all features undefied
     pub const fn new_undef() -> Self {
         SyllableFeatures {
             features: [FeatureState::UNDEF; SYL_FEATURE_COUNT as usize],
+            tone: None,
         }
     }
 
+    /// the same features, with tone `tone`
+    pub const fn with_tone(mut self, tone: Tone) -> Self {
+        self.tone = Some(tone);
+        self
+    }
+
     /// returns true if this segment matches `pattern`'s defined features.  
     /// to return true:
     /// - if a feature is defined in `pattern`, it must be defined in this segment
     /// - if a feature is `POS` or `NEG` in `pattern`, it must be identical in this segment
     /// - if a features is `NA` in `pattern`, it can be `POS`, `NEG`, or `NA` in this segment
     ///
+    /// - if a feature is a variable (`α`, `-α`) in `pattern`, it must be `POS` or `NEG` in this
+    ///   segment.
+    ///
+    /// - if `pattern` has a tone, this syllable must have the same tone
+    ///
     /// otherwise, returns false.
     pub fn matches(&self, pattern: &SyllableFeatures) -> bool {
+        if pattern.tone.is_some() && pattern.tone != self.tone {
+            return false;
+        }
         for i in 0..(SYL_FEATURE_COUNT as usize) {
-            if pattern.features[i] == UNDEF || pattern.features[i] == self.features[i] {
+            let is_variable_match =
+                pattern.features[i].is_variable() && matches!(self.features[i], POS | NEG);
+            if pattern.features[i] == UNDEF
+                || pattern.features[i] == self.features[i]
+                || is_variable_match
+            {
                 continue;
             } else {
                 return false;
@@ -40,6 +78,43 @@ impl SyllableFeatures {
         true
     }
 
+    /// bind the variables in `pattern` to this syllable's feature values.
+    /// returns false if a variable would be bound to two different values.
+    pub fn bind_variables(
+        &self,
+        pattern: &SyllableFeatures,
+        bindings: &mut VariableBindings,
+    ) -> bool {
+        pattern
+            .features
+            .iter()
+            .zip(self.features.iter())
+            .all(|(p, s)| p.bind(*s, bindings))
+    }
+
+    /// replace the variables in this feature set by their bound values.
+    /// returns None if a variable isn't bound.
+    pub fn substitute_variables(&self, bindings: &VariableBindings) -> Option<Self> {
+        let mut result = self.clone();
+        for feature in &mut result.features {
+            *feature = feature.substitute(bindings)?;
+        }
+        Some(result)
+    }
+
+    /// How strong the stress of the syllable is: 2 for primary stress, 1 for secondary stress,
+    /// and 0 if it is unstressed or its stress is undefined
+    pub fn stress_level(&self) -> u8 {
+        match (
+            self.features[STRESS as usize],
+            self.features[PRIMARY as usize],
+        ) {
+            (POS, NEG) => 1,
+            (POS, _) => 2,
+            _ => 0,
+        }
+    }
+
     /// Returns true if all features are defined
     pub fn is_complete(&self) -> bool {
         for i in 0..(SYL_FEATURE_COUNT as usize) {
@@ -51,14 +126,16 @@ impl SyllableFeatures {
         true
     }
 
-    pub const fn from_features(features: [FeatureState; 1]) -> SyllableFeatures {
-        Self { features }
+    pub const fn from_features(
+        features: [FeatureState; SYL_FEATURE_COUNT as usize],
+    ) -> SyllableFeatures {
+        Self::new(features)
     }
 }
 
 impl From<[FeatureState; SYL_FEATURE_COUNT as usize]> for SyllableFeatures {
     fn from(features: [FeatureState; SYL_FEATURE_COUNT as usize]) -> Self {
-        Self { features }
+        Self::new(features)
     }
 }
 
@@ -68,9 +145,12 @@ impl Add<SyllableFeatures> for SyllableFeatures {
     /// adds the features of the rhs segment to the lhs segment.
     /// if the rhs segment is complete (completely defined), the result is the rhs.
     /// if the feature is defined in the rhs, it will be overwritten in result,
-    /// otherwise, the lsh's feature value will be used.
+    /// otherwise, the lsh's feature value will be used. The same goes for the tone.
     fn add(self, s2: Self) -> Self {
         let mut result = self.clone();
+        if s2.tone.is_some() {
+            result.tone = s2.tone;
+        }
         for i in 0..(SYL_FEATURE_COUNT as usize) {
             if s2.features[i] != UNDEF {
                 result.features[i] = s2.features[i];
@@ -1,3 +1,8 @@
 // The features that a syllable can have
-pub const SYL_FEATURE_COUNT: u8 = 1;
-// syllable features : [stress]
+pub const SYL_FEATURE_COUNT: u8 = 2;
+// syllable features : [stress, primary]
+pub const STRESS: u8 = 0;
+// primary stress, for stressed syllables. secondary stress is [+stress -primary]
+pub const PRIMARY: u8 = 1;
+
+pub const SYL_FEATURE_NAMES: [&str; SYL_FEATURE_COUNT as usize] = ["stress", "primary"];
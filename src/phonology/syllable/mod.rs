@@ -1,5 +1,7 @@
 mod base;
 mod feature;
+mod tone;
 
 pub use base::*;
 pub use feature::*;
+pub use tone::*;
@@ -0,0 +1,206 @@
+use std::fmt;
+
+use nom::{
+    Err, IResult,
+    error::{Error, ErrorKind},
+};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::error::{self, Result};
+
+/// The most levels a contour tone goes through, like `˨˩˦` (214)
+pub const MAX_TONE_LEVELS: usize = 3;
+
+/// Chao tone letters, with their pitch levels
+pub const TONE_LETTERS: [(char, u8); 5] = [('˥', 5), ('˦', 4), ('˧', 3), ('˨', 2), ('˩', 1)];
+
+/// Superscript tone numbers, with their pitch levels
+pub const TONE_NUMBERS: [(char, u8); 5] = [('¹', 1), ('²', 2), ('³', 3), ('⁴', 4), ('⁵', 5)];
+
+/// Tone diacritics, placed over the syllable's nucleus, with the levels they stand for
+pub const TONE_DIACRITICS: [(char, &[u8]); 10] = [
+    // extra high
+    ('\u{030B}', &[5]),
+    // high
+    ('\u{0301}', &[4]),
+    // mid
+    ('\u{0304}', &[3]),
+    // low
+    ('\u{0300}', &[2]),
+    // extra low
+    ('\u{030F}', &[1]),
+    // falling
+    ('\u{0302}', &[5, 1]),
+    // rising
+    ('\u{030C}', &[1, 5]),
+    // high rising
+    ('\u{1DC4}', &[4, 5]),
+    // low rising
+    ('\u{1DC5}', &[1, 2]),
+    // rising-falling
+    ('\u{1DC8}', &[3, 4, 3]),
+];
+
+/// A tone, as the pitch levels it goes through, from 1 (lowest) to 5 (highest) like Chao tone
+/// numbers. A level tone has a single level (`˥` is `[5]`), a contour tone has two or three (`˨˩˦`
+/// is `[2, 1, 4]`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Tone {
+    levels: [u8; MAX_TONE_LEVELS],
+    len: u8,
+}
+
+impl Tone {
+    /// A tone through `levels`. Repeated levels are merged, so `55` is the level tone `5`.
+    pub fn new(levels: &[u8]) -> Result<Self> {
+        let mut tone = Tone {
+            levels: [0; MAX_TONE_LEVELS],
+            len: 0,
+        };
+        for level in levels {
+            if !(1..=5).contains(level) {
+                return Err(error::Error::segment(
+                    format!("tone level {level} isn't between 1 and 5"),
+                    None,
+                ));
+            }
+            if tone.levels().last() == Some(level) {
+                continue;
+            }
+            if tone.len as usize == MAX_TONE_LEVELS {
+                return Err(error::Error::segment(
+                    format!("a tone has at most {MAX_TONE_LEVELS} levels"),
+                    None,
+                ));
+            }
+            tone.levels[tone.len as usize] = *level;
+            tone.len += 1;
+        }
+        if tone.len == 0 {
+            return Err(error::Error::segment("a tone needs a level", None));
+        }
+        Ok(tone)
+    }
+
+    /// The level tone at `level`, from 1 to 5
+    pub const fn level(level: u8) -> Self {
+        assert!(level >= 1 && level <= 5, "tone levels go from 1 to 5");
+        Tone {
+            levels: [level, 0, 0],
+            len: 1,
+        }
+    }
+
+    /// The pitch levels of the tone, from 1 (lowest) to 5 (highest)
+    pub fn levels(&self) -> &[u8] {
+        &self.levels[..self.len as usize]
+    }
+
+    /// true for rising, falling or more complex tones
+    pub fn is_contour(&self) -> bool {
+        self.len > 1
+    }
+
+    /// The tone in Chao tone letters, like `˨˩˦`
+    pub fn letters(&self) -> String {
+        self.format_with(&TONE_LETTERS)
+    }
+
+    /// The tone in superscript numbers, like `²¹⁴`
+    pub fn numbers(&self) -> String {
+        self.format_with(&TONE_NUMBERS)
+    }
+
+    fn format_with(&self, symbols: &[(char, u8)]) -> String {
+        self.levels()
+            .iter()
+            .filter_map(|level| symbols.iter().find(|(_, l)| l == level))
+            .map(|(symbol, _)| symbol)
+            .collect()
+    }
+}
+
+impl fmt::Display for Tone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.letters())
+    }
+}
+
+/// The tone of a tone diacritic, like `[5, 1]` for the circumflex in `â`
+pub fn tone_of_diacritic(diacritic: char) -> Option<Tone> {
+    TONE_DIACRITICS
+        .iter()
+        .find(|(symbol, _)| *symbol == diacritic)
+        .and_then(|(_, levels)| Tone::new(levels).ok())
+}
+
+/// Parse a tone written in Chao tone letters (`˨˩˦`) or superscript numbers (`²¹⁴`)
+pub fn parse_tone(input: &str) -> IResult<&str, Tone> {
+    let symbols = [TONE_LETTERS, TONE_NUMBERS]
+        .into_iter()
+        .find(|symbols| {
+            input
+                .chars()
+                .next()
+                .is_some_and(|c| symbols.iter().any(|(s, _)| *s == c))
+        })
+        .ok_or(Err::Error(Error::new(input, ErrorKind::Char)))?;
+
+    let mut levels = vec![];
+    let mut end = 0;
+    for c in input.chars() {
+        match symbols.iter().find(|(s, _)| *s == c) {
+            Some((_, level)) => levels.push(*level),
+            None => break,
+        }
+        end += c.len_utf8();
+    }
+
+    let tone = Tone::new(&levels).map_err(|_| Err::Error(Error::new(input, ErrorKind::Verify)))?;
+    Ok((&input[end..], tone))
+}
+
+/// Length of the character at the start of `input` and the combining marks following it
+fn combining_cluster_len(input: &str) -> usize {
+    let mut chars = input.char_indices();
+    chars.next();
+    chars
+        .find(|(_, c)| !is_combining(*c))
+        .map_or(input.len(), |(idx, _)| idx)
+}
+
+fn is_combining(c: char) -> bool {
+    matches!(c, '\u{0300}'..='\u{036F}' | '\u{1DC0}'..='\u{1DFF}')
+}
+
+/// Split the tone diacritic off the character at the start of `input` and its combining marks,
+/// like `á` into `a` and a high tone. Returns the cluster's length, the cluster without its tone
+/// diacritic, and the tone, or None if the cluster has no tone diacritic (or several).
+pub(crate) fn split_tone_diacritic(input: &str) -> Option<(usize, String, Tone)> {
+    let cluster_len = combining_cluster_len(input);
+    let (tones, rest): (String, String) = input[..cluster_len]
+        .nfd()
+        .partition(|c| tone_of_diacritic(*c).is_some());
+    let mut tones = tones.chars();
+    match (tones.next(), tones.next()) {
+        (Some(diacritic), None) if !rest.is_empty() => {
+            Some((cluster_len, rest, tone_of_diacritic(diacritic)?))
+        }
+        _ => None,
+    }
+}
+
+/// Recognize a tone in a rule: tone letters or numbers, a tone diacritic, or a character with a
+/// tone diacritic, like `á`
+pub(crate) fn parse_tone_mark(input: &str) -> IResult<&str, &str> {
+    if let Ok((rest, _)) = parse_tone(input) {
+        return Ok((rest, &input[..input.len() - rest.len()]));
+    }
+    let first = input.chars().next();
+    let is_toned = first.is_some_and(|c| tone_of_diacritic(c).is_some())
+        || split_tone_diacritic(input).is_some();
+    match first {
+        Some(c) if is_toned => Ok((&input[c.len_utf8()..], &input[..c.len_utf8()])),
+        _ => Err(Err::Error(Error::new(input, ErrorKind::Char))),
+    }
+}
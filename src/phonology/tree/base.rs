@@ -57,7 +57,7 @@ impl<T0, T1, T2> Depth3Tree<T0, T1, T2> {
     /// Insert a node at layer 0 at `index` relative to other nodes at layer 0
     pub fn insert_depth_0(&mut self, index: usize, element: T0) {
         self.layer_0.insert(index, element);
-        for (_, parent_idx) in &mut self.layer_2 {
+        for (_, parent_idx) in &mut self.layer_1 {
             if *parent_idx >= index {
                 *parent_idx += 1;
             }
@@ -141,30 +141,90 @@ impl<T0, T1, T2> Depth3Tree<T0, T1, T2> {
         true
     }
 
+    /// Remove the nodes of layers 0 and 1 that have no children, so that all leaves are depth 3.
+    pub fn prune(&mut self) {
+        let mut is_parent = vec![false; self.layer_1.len()];
+        for (_, parent_idx) in &self.layer_2 {
+            is_parent[*parent_idx] = true;
+        }
+        let new_indices = retain_nodes(&mut self.layer_1, &is_parent);
+        for (_, parent_idx) in &mut self.layer_2 {
+            *parent_idx = new_indices[*parent_idx];
+        }
+
+        let mut is_parent = vec![false; self.layer_0.len()];
+        for (_, parent_idx) in &self.layer_1 {
+            is_parent[*parent_idx] = true;
+        }
+        let new_indices = retain_nodes(&mut self.layer_0, &is_parent);
+        for (_, parent_idx) in &mut self.layer_1 {
+            *parent_idx = new_indices[*parent_idx];
+        }
+    }
+
     /// Replace a section of the tree delimited by a range on leaf nodes, up to the root.
     /// The leaf node range creates two "spines", which cut out a subtree, from the root.
     /// This zone is replaced with another UniformDepth3Tree.
     /// nodes on the spines are replaced by the corresponding nodes on the edge of the inserted
     /// subtree.
     pub fn replace_range(
-        mut self,
+        self,
         leaf_range: Range<usize>,
-        mut replace_with: Depth3Tree<T0, T1, T2>,
+        replace_with: Depth3Tree<T0, T1, T2>,
     ) -> Result<Self> {
         if leaf_range.start >= self.layer_2.len() {
-            return Err(Error::other("Invalid range"));
+            return Err(Error::tree("Invalid range"));
         }
-        if leaf_range.end > self.layer_2.len() {
-            return Err(Error::other("Invalid range"));
+        if leaf_range.end > self.layer_2.len() || leaf_range.end <= leaf_range.start {
+            return Err(Error::tree("Invalid range"));
         }
 
         // construct left and right spines
-        let l_spine_2 = leaf_range.start;
-        let l_spine_1 = self.layer_2[l_spine_2].1;
-        let l_spine_0 = self.layer_1[l_spine_1].1;
         // (range.end is not inclusive, we want the spine to include the replacement zone)
-        let r_spine_2 = leaf_range.end - 1;
-        let r_spine_1 = self.layer_2[r_spine_2].1;
+        let l_spine_1 = self.layer_2[leaf_range.start].1;
+        let r_spine_1 = self.layer_2[leaf_range.end - 1].1;
+
+        self.splice(leaf_range, l_spine_1, r_spine_1, replace_with)
+    }
+
+    /// Insert a subtree between the leaves `leaf_idx - 1` and `leaf_idx`.
+    /// The insertion is spliced into the layer 1 nodes of the leaves on the `attach` sides, as
+    /// with `replace_range`: they are replaced by the edge nodes of the inserted subtree. When
+    /// there is no leaf on an `attach` side (at the edge of the tree), the other side is used.
+    /// Inserting into a tree without any node returns the inserted subtree.
+    pub fn insert_at(
+        self,
+        leaf_idx: usize,
+        attach: Attach,
+        insert: Depth3Tree<T0, T1, T2>,
+    ) -> Result<Self> {
+        if self.layer_0.is_empty() {
+            return Ok(insert);
+        }
+        if self.layer_2.is_empty() || leaf_idx > self.layer_2.len() {
+            return Err(Error::tree("Invalid insertion index"));
+        }
+
+        let (l_spine_1, r_spine_1) = insertion_spines(&self.layer_2, leaf_idx, attach);
+        self.splice(leaf_idx..leaf_idx, l_spine_1, r_spine_1, insert)
+    }
+
+    /// Replace the layer 2 nodes in `leaf_range`, and the spines from the layer 1 nodes
+    /// `l_spine_1` and `r_spine_1` up to the root, by `replace_with`. Leaves of the spine nodes
+    /// that aren't in `leaf_range` go under the edge nodes of `replace_with`.
+    fn splice(
+        mut self,
+        leaf_range: Range<usize>,
+        l_spine_1: usize,
+        r_spine_1: usize,
+        mut replace_with: Depth3Tree<T0, T1, T2>,
+    ) -> Result<Self> {
+        if replace_with.layer_0.is_empty() || replace_with.layer_1.is_empty() {
+            // nothing to attach the remaining leaves of the spines to
+            return Err(Error::tree("Replacement tree has no layer 1 node"));
+        }
+
+        let l_spine_0 = self.layer_1[l_spine_1].1;
         let r_spine_0 = self.layer_1[r_spine_1].1;
 
         // adjust replacement's indices
@@ -188,7 +248,7 @@ impl<T0, T1, T2> Depth3Tree<T0, T1, T2> {
         let adjustment = replace_with.layer_1.len() as isize - (r_spine_1 + 1 - l_spine_1) as isize;
         self.layer_2
             .iter_mut()
-            .skip(r_spine_2 + 1)
+            .skip(leaf_range.end)
             .for_each(|(_, idx)| *idx = ((*idx) as isize + adjustment) as usize);
 
         // replace layers, from left to right spine (inclusive !)
@@ -198,8 +258,7 @@ impl<T0, T1, T2> Depth3Tree<T0, T1, T2> {
         let range_1 = l_spine_1..=r_spine_1;
         self.layer_1.splice(range_1, replace_with.layer_1);
 
-        let range_2 = l_spine_2..=r_spine_2;
-        self.layer_2.splice(range_2, replace_with.layer_2);
+        self.layer_2.splice(leaf_range, replace_with.layer_2);
 
         Ok(self)
     }
@@ -272,3 +331,56 @@ impl<T0, T1, T2> Default for Depth3Tree<T0, T1, T2> {
         Self::new()
     }
 }
+
+/// Keep the nodes of `layer` for which `keep` is true. Returns the new index of each kept node,
+/// by old index.
+pub(super) fn retain_nodes<T>(layer: &mut Vec<T>, keep: &[bool]) -> Vec<usize> {
+    let mut new_indices = Vec::with_capacity(keep.len());
+    let mut next_idx = 0;
+    for k in keep {
+        new_indices.push(next_idx);
+        if *k {
+            next_idx += 1;
+        }
+    }
+
+    let mut keep = keep.iter();
+    layer.retain(|_| *keep.next().unwrap());
+    new_indices
+}
+
+/// The parents of the leaves on the `attach` sides of the insertion point before `leaf_idx`,
+/// from the left. At the edge of a tree, the leaf on the other side is used.
+pub(super) fn insertion_spines<T>(
+    leaves: &[(T, usize)],
+    leaf_idx: usize,
+    attach: Attach,
+) -> (usize, usize) {
+    let attach_left = match attach {
+        Attach::Left | Attach::Both => leaf_idx > 0,
+        Attach::Right => leaf_idx == leaves.len(),
+    };
+    let attach_right = match attach {
+        Attach::Right | Attach::Both => leaf_idx < leaves.len(),
+        Attach::Left => leaf_idx == 0,
+    };
+    let before = || leaves[leaf_idx - 1].1;
+    let after = || leaves[leaf_idx].1;
+    (
+        if attach_left { before() } else { after() },
+        if attach_right { after() } else { before() },
+    )
+}
+
+/// The side a subtree inserted between two leaves attaches to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Attach {
+    /// into the parent of the leaf before the insertion point
+    #[default]
+    Left,
+    /// into the parent of the leaf after the insertion point
+    Right,
+    /// into the parents of the leaves on both sides, which are replaced by the edge nodes of the
+    /// inserted subtree: the subtree replaces the boundary between the two leaves
+    Both,
+}
@@ -50,3 +50,52 @@ macro_rules! d3tree {
         tree
     }};
 }
+
+/// macro for easy construction of a `Depth5Tree`, like `d3tree`. example :
+///
+/// ```
+/// use getheode::d5tree;
+/// let x = d5tree![
+///     'a' => [
+///         'b' => [
+///             'c' => [
+///                 'd' => ['e', 'f'],
+///             ],
+///         ],
+///     ],
+/// ];
+/// ```
+#[macro_export]
+macro_rules! d5tree {
+    (
+        $( $l0:expr => [
+            $( $l1:expr => [
+                $( $l2:expr => [
+                    $( $l3:expr => [
+                        $( $l4:expr ),* $(,)?
+                    ] ),* $(,)?
+                ] ),* $(,)?
+            ] ),* $(,)?
+        ] ),* $(,)?
+    ) => {{
+        #[allow(unused_mut)]
+        let mut tree = $crate::phonology::tree::Depth5Tree::new();
+        $(
+            tree.push_depth_0($l0);
+            $(
+                tree.push_depth_1($l1);
+                $(
+                    tree.push_depth_2($l2);
+                    $(
+                        tree.push_depth_3($l3);
+                        $(
+                            tree.push_depth_4($l4);
+                        )*
+                    )*
+                )*
+            )*
+        )*
+
+        tree
+    }};
+}
@@ -0,0 +1,407 @@
+use std::{
+    fmt::{Debug, Write as _},
+    ops::Range,
+};
+
+use crate::error::*;
+
+use crate::phonology::tree::{
+    Attach, Depth3Tree,
+    base::{insertion_spines, retain_nodes},
+};
+
+/// A uniform 5-depth tree, like `Depth3Tree` with two more layers. Each layer has its own node
+/// data type, and node order relative to other nodes at the same depth is important.
+///
+/// Invariants :
+/// - all leaf nodes are depth 5 (uniform)
+///
+/// Use :
+/// This represents the prosodic hierarchy of phonological strings: phrases, words, feet,
+/// syllables and segments.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Depth5Tree<T0, T1, T2, T3, T4> {
+    pub(super) layer_0: Vec<T0>, // parent is always root for these nodes
+    pub(super) layer_1: Vec<(T1, usize)>, // data with index of parent in `layer_0`
+    pub(super) layer_2: Vec<(T2, usize)>, // data with index of parent in `layer_1`
+    pub(super) layer_3: Vec<(T3, usize)>, // data with index of parent in `layer_2`
+    pub(super) layer_4: Vec<(T4, usize)>, // data with index of parent in `layer_3`
+}
+
+impl<T0, T1, T2, T3, T4> Depth5Tree<T0, T1, T2, T3, T4> {
+    pub fn new() -> Self {
+        Self {
+            layer_0: vec![],
+            layer_1: vec![],
+            layer_2: vec![],
+            layer_3: vec![],
+            layer_4: vec![],
+        }
+    }
+
+    pub fn push_depth_0(&mut self, element: T0) {
+        self.layer_0.push(element);
+    }
+
+    pub fn push_depth_1(&mut self, element: T1) {
+        let last_idx = self.layer_0.len() - 1;
+        self.layer_1.push((element, last_idx));
+    }
+
+    pub fn push_depth_2(&mut self, element: T2) {
+        let last_idx = self.layer_1.len() - 1;
+        self.layer_2.push((element, last_idx));
+    }
+
+    pub fn push_depth_3(&mut self, element: T3) {
+        let last_idx = self.layer_2.len() - 1;
+        self.layer_3.push((element, last_idx));
+    }
+
+    pub fn push_depth_4(&mut self, element: T4) {
+        let last_idx = self.layer_3.len() - 1;
+        self.layer_4.push((element, last_idx));
+    }
+
+    /// Tests if the parent indices of each layer are valid and never descend
+    pub fn test_invariants(&self) -> bool {
+        are_parents_valid(&self.layer_1, self.layer_0.len())
+            && are_parents_valid(&self.layer_2, self.layer_1.len())
+            && are_parents_valid(&self.layer_3, self.layer_2.len())
+            && are_parents_valid(&self.layer_4, self.layer_3.len())
+    }
+
+    /// Tests if all leaf nodes of the tree are depth 5, in other words, is the tree "uniform"
+    pub fn are_leaves_depth_5(&self) -> bool {
+        [
+            is_parent(&self.layer_1, self.layer_0.len()),
+            is_parent(&self.layer_2, self.layer_1.len()),
+            is_parent(&self.layer_3, self.layer_2.len()),
+            is_parent(&self.layer_4, self.layer_3.len()),
+        ]
+        .iter()
+        .all(|is_parent| is_parent.iter().all(|x| *x))
+    }
+
+    /// Remove the nodes of layers 0 to 3 that have no children, so that all leaves are depth 5.
+    pub fn prune(&mut self) {
+        let is_parent_3 = is_parent(&self.layer_4, self.layer_3.len());
+        let new_indices = retain_nodes(&mut self.layer_3, &is_parent_3);
+        remap_parents(&mut self.layer_4, &new_indices);
+
+        let is_parent_2 = is_parent(&self.layer_3, self.layer_2.len());
+        let new_indices = retain_nodes(&mut self.layer_2, &is_parent_2);
+        remap_parents(&mut self.layer_3, &new_indices);
+
+        let is_parent_1 = is_parent(&self.layer_2, self.layer_1.len());
+        let new_indices = retain_nodes(&mut self.layer_1, &is_parent_1);
+        remap_parents(&mut self.layer_2, &new_indices);
+
+        let is_parent_0 = is_parent(&self.layer_1, self.layer_0.len());
+        let new_indices = retain_nodes(&mut self.layer_0, &is_parent_0);
+        remap_parents(&mut self.layer_1, &new_indices);
+    }
+
+    /// Replace a section of the tree delimited by a range on leaf nodes, up to the root, like
+    /// `Depth3Tree::replace_range`. Nodes on the spines are replaced by the corresponding nodes
+    /// on the edge of the inserted subtree.
+    pub fn replace_range(self, leaf_range: Range<usize>, replace_with: Self) -> Result<Self> {
+        if leaf_range.start >= self.layer_4.len() {
+            return Err(Error::tree("Invalid range"));
+        }
+        if leaf_range.end > self.layer_4.len() || leaf_range.end <= leaf_range.start {
+            return Err(Error::tree("Invalid range"));
+        }
+
+        let l_spine_3 = self.layer_4[leaf_range.start].1;
+        let r_spine_3 = self.layer_4[leaf_range.end - 1].1;
+
+        self.splice(leaf_range, l_spine_3, r_spine_3, replace_with)
+    }
+
+    /// Insert a subtree between the leaves `leaf_idx - 1` and `leaf_idx`, like
+    /// `Depth3Tree::insert_at`. The insertion is spliced into the layer 3 nodes of the leaves on
+    /// the `attach` sides.
+    pub fn insert_at(self, leaf_idx: usize, attach: Attach, insert: Self) -> Result<Self> {
+        if self.layer_0.is_empty() {
+            return Ok(insert);
+        }
+        if self.layer_4.is_empty() || leaf_idx > self.layer_4.len() {
+            return Err(Error::tree("Invalid insertion index"));
+        }
+
+        let (l_spine_3, r_spine_3) = insertion_spines(&self.layer_4, leaf_idx, attach);
+        self.splice(leaf_idx..leaf_idx, l_spine_3, r_spine_3, insert)
+    }
+
+    /// Replace the layer 4 nodes in `leaf_range`, and the spines from the layer 3 nodes
+    /// `l_spine_3` and `r_spine_3` up to the root, by `replace_with`. Children of the spine nodes
+    /// that aren't replaced go under the edge nodes of `replace_with`.
+    fn splice(
+        mut self,
+        leaf_range: Range<usize>,
+        l_spine_3: usize,
+        r_spine_3: usize,
+        mut replace_with: Self,
+    ) -> Result<Self> {
+        if replace_with.layer_3.is_empty() || !replace_with.test_invariants() {
+            // nothing to attach the remaining leaves of the spines to
+            return Err(Error::tree("Replacement tree has no layer 3 node"));
+        }
+
+        let (l_spine_2, r_spine_2) = (self.layer_3[l_spine_3].1, self.layer_3[r_spine_3].1);
+        let (l_spine_1, r_spine_1) = (self.layer_2[l_spine_2].1, self.layer_2[r_spine_2].1);
+        let (l_spine_0, r_spine_0) = (self.layer_1[l_spine_1].1, self.layer_1[r_spine_1].1);
+
+        // adjust replacement's indices
+        offset_parents(&mut replace_with.layer_1, l_spine_0);
+        offset_parents(&mut replace_with.layer_2, l_spine_1);
+        offset_parents(&mut replace_with.layer_3, l_spine_2);
+        offset_parents(&mut replace_with.layer_4, l_spine_3);
+
+        // adjust parent indices after replacement zone
+        let adjustment =
+            |replaced: &Range<usize>, new_len: usize| new_len as isize - replaced.len() as isize;
+        let spine_0 = l_spine_0..(r_spine_0 + 1);
+        let spine_1 = l_spine_1..(r_spine_1 + 1);
+        let spine_2 = l_spine_2..(r_spine_2 + 1);
+        let spine_3 = l_spine_3..(r_spine_3 + 1);
+        shift_parents(
+            &mut self.layer_1[spine_1.end..],
+            adjustment(&spine_0, replace_with.layer_0.len()),
+        );
+        shift_parents(
+            &mut self.layer_2[spine_2.end..],
+            adjustment(&spine_1, replace_with.layer_1.len()),
+        );
+        shift_parents(
+            &mut self.layer_3[spine_3.end..],
+            adjustment(&spine_2, replace_with.layer_2.len()),
+        );
+        shift_parents(
+            &mut self.layer_4[leaf_range.end..],
+            adjustment(&spine_3, replace_with.layer_3.len()),
+        );
+
+        // replace layers, from left to right spine
+        self.layer_0.splice(spine_0, replace_with.layer_0);
+        self.layer_1.splice(spine_1, replace_with.layer_1);
+        self.layer_2.splice(spine_2, replace_with.layer_2);
+        self.layer_3.splice(spine_3, replace_with.layer_3);
+        self.layer_4.splice(leaf_range, replace_with.layer_4);
+
+        Ok(self)
+    }
+
+    pub fn layer_0(&self) -> &[T0] {
+        self.layer_0.as_slice()
+    }
+
+    pub fn layer_1(&self) -> &[(T1, usize)] {
+        self.layer_1.as_slice()
+    }
+
+    pub fn layer_2(&self) -> &[(T2, usize)] {
+        self.layer_2.as_slice()
+    }
+
+    pub fn layer_3(&self) -> &[(T3, usize)] {
+        self.layer_3.as_slice()
+    }
+
+    pub fn layer_4(&self) -> &[(T4, usize)] {
+        self.layer_4.as_slice()
+    }
+
+    pub fn get_depth_0_mut(&mut self, idx: usize) -> &mut T0 {
+        &mut self.layer_0[idx]
+    }
+
+    pub fn get_depth_1_mut(&mut self, idx: usize) -> &mut T1 {
+        &mut self.layer_1[idx].0
+    }
+
+    pub fn get_depth_2_mut(&mut self, idx: usize) -> &mut T2 {
+        &mut self.layer_2[idx].0
+    }
+
+    pub fn get_depth_3_mut(&mut self, idx: usize) -> &mut T3 {
+        &mut self.layer_3[idx].0
+    }
+
+    pub fn get_depth_4_mut(&mut self, idx: usize) -> &mut T4 {
+        &mut self.layer_4[idx].0
+    }
+
+    /// Get number of nodes with depth 0
+    pub fn len_0(&self) -> usize {
+        self.layer_0.len()
+    }
+
+    /// Get number of nodes with depth 1
+    pub fn len_1(&self) -> usize {
+        self.layer_1.len()
+    }
+
+    /// Get number of nodes with depth 2
+    pub fn len_2(&self) -> usize {
+        self.layer_2.len()
+    }
+
+    /// Get number of nodes with depth 3
+    pub fn len_3(&self) -> usize {
+        self.layer_3.len()
+    }
+
+    /// Get number of nodes with depth 4
+    pub fn len_4(&self) -> usize {
+        self.layer_4.len()
+    }
+
+    /// Indices in layer 1 of the children of node `idx` of layer 0
+    pub fn children_1(&self, idx: usize) -> Range<usize> {
+        children(&self.layer_1, idx)
+    }
+
+    /// Indices in layer 2 of the children of node `idx` of layer 1
+    pub fn children_2(&self, idx: usize) -> Range<usize> {
+        children(&self.layer_2, idx)
+    }
+
+    /// Indices in layer 3 of the children of node `idx` of layer 2
+    pub fn children_3(&self, idx: usize) -> Range<usize> {
+        children(&self.layer_3, idx)
+    }
+
+    /// Indices in layer 4 of the children of node `idx` of layer 3
+    pub fn children_4(&self, idx: usize) -> Range<usize> {
+        children(&self.layer_4, idx)
+    }
+
+    /// The tree with the same structure, where the data of each node is mapped by the function
+    /// of its layer. None if any function returns None.
+    pub fn try_map<U0, U1, U2, U3, U4>(
+        &self,
+        mut f0: impl FnMut(&T0) -> Option<U0>,
+        mut f1: impl FnMut(&T1) -> Option<U1>,
+        mut f2: impl FnMut(&T2) -> Option<U2>,
+        mut f3: impl FnMut(&T3) -> Option<U3>,
+        mut f4: impl FnMut(&T4) -> Option<U4>,
+    ) -> Option<Depth5Tree<U0, U1, U2, U3, U4>> {
+        Some(Depth5Tree {
+            layer_0: self.layer_0.iter().map(&mut f0).collect::<Option<_>>()?,
+            layer_1: map_layer(&self.layer_1, &mut f1)?,
+            layer_2: map_layer(&self.layer_2, &mut f2)?,
+            layer_3: map_layer(&self.layer_3, &mut f3)?,
+            layer_4: map_layer(&self.layer_4, &mut f4)?,
+        })
+    }
+
+    pub fn pretty_format(&self) -> String
+    where
+        T0: Debug,
+        T1: Debug,
+        T2: Debug,
+        T3: Debug,
+        T4: Debug,
+    {
+        let mut result = String::new();
+
+        for (i0, l0) in self.layer_0.iter().enumerate() {
+            write!(&mut result, "{:#?}", l0).unwrap();
+            for i1 in self.children_1(i0) {
+                write!(&mut result, "+ {:#?}", self.layer_1[i1].0).unwrap();
+                for i2 in self.children_2(i1) {
+                    write!(&mut result, "+-- {:#?}", self.layer_2[i2].0).unwrap();
+                    for i3 in self.children_3(i2) {
+                        write!(&mut result, "+---- {:#?}", self.layer_3[i3].0).unwrap();
+                        for i4 in self.children_4(i3) {
+                            write!(&mut result, "+------ {:#?}", self.layer_4[i4].0).unwrap();
+                        }
+                    }
+                }
+            }
+        }
+
+        result
+    }
+}
+
+impl<T0, T1, T2, T3, T4> Default for Depth5Tree<T0, T1, T2, T3, T4> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T0: Default, T1, T2: Default, T3, T4> From<Depth3Tree<T1, T3, T4>>
+    for Depth5Tree<T0, T1, T2, T3, T4>
+{
+    /// The 3-depth tree under a single layer 0 node, where each layer 1 node has a single default
+    /// child: the layers of `tree` become layers 1, 3 and 4.
+    fn from(tree: Depth3Tree<T1, T3, T4>) -> Self {
+        let layer_1_len = tree.layer_0.len();
+        let layer_0 = if layer_1_len == 0 {
+            vec![]
+        } else {
+            vec![T0::default()]
+        };
+        Self {
+            layer_0,
+            layer_1: tree.layer_0.into_iter().map(|node| (node, 0)).collect(),
+            layer_2: (0..layer_1_len).map(|idx| (T2::default(), idx)).collect(),
+            layer_3: tree.layer_1,
+            layer_4: tree.layer_2,
+        }
+    }
+}
+
+/// the nodes of `layer` mapped by `f`, with the same parents
+fn map_layer<T, U>(
+    layer: &[(T, usize)],
+    f: &mut impl FnMut(&T) -> Option<U>,
+) -> Option<Vec<(U, usize)>> {
+    layer
+        .iter()
+        .map(|(node, parent)| Some((f(node)?, *parent)))
+        .collect()
+}
+
+/// true if the parent indices of `layer` never descend, and are less than `parent_len`
+fn are_parents_valid<T>(layer: &[(T, usize)], parent_len: usize) -> bool {
+    layer.is_sorted_by_key(|(_, parent)| *parent)
+        && layer.iter().all(|(_, parent)| *parent < parent_len)
+}
+
+/// for each of the `parent_len` nodes of the parent layer, whether it has children in `layer`
+fn is_parent<T>(layer: &[(T, usize)], parent_len: usize) -> Vec<bool> {
+    let mut is_parent = vec![false; parent_len];
+    for (_, parent_idx) in layer {
+        is_parent[*parent_idx] = true;
+    }
+    is_parent
+}
+
+/// the indices of the children of `parent_idx` in `layer`, which is sorted by parent
+fn children<T>(layer: &[(T, usize)], parent_idx: usize) -> Range<usize> {
+    let start = layer.partition_point(|(_, parent)| *parent < parent_idx);
+    let end = layer.partition_point(|(_, parent)| *parent <= parent_idx);
+    start..end
+}
+
+fn remap_parents<T>(layer: &mut [(T, usize)], new_indices: &[usize]) {
+    for (_, parent_idx) in layer {
+        *parent_idx = new_indices[*parent_idx];
+    }
+}
+
+fn offset_parents<T>(layer: &mut [(T, usize)], offset: usize) {
+    for (_, parent_idx) in layer {
+        *parent_idx += offset;
+    }
+}
+
+fn shift_parents<T>(layer: &mut [(T, usize)], adjustment: isize) {
+    for (_, parent_idx) in layer {
+        *parent_idx = (*parent_idx as isize + adjustment) as usize;
+    }
+}
@@ -1,9 +1,11 @@
 mod base;
 mod constructor;
+mod depth5;
 
 pub mod iter;
 
 pub use base::*;
+pub use depth5::*;
 
 #[cfg(test)]
 mod test;
@@ -1,6 +1,10 @@
-use crate::{d3tree, phonology::tree::Depth3Tree};
+use crate::{
+    d3tree,
+    phonology::tree::{Attach, Depth3Tree},
+};
 
 mod depth3 {
+    use super::Attach;
     use crate::d3tree;
 
     #[test]
@@ -143,6 +147,230 @@ mod depth3 {
 
         assert_eq!(res, expected);
     }
+
+    #[test]
+    fn test_prune() {
+        let mut tree = d3tree![
+            0 => [
+                3 => [7, 8],
+                4 => [],
+            ],
+            1 => [5 => []],
+            2 => [6 => [9]],
+        ];
+
+        let expected = d3tree![
+            0 => [3 => [7, 8]],
+            2 => [6 => [9]],
+        ];
+
+        tree.prune();
+
+        assert_eq!(tree, expected);
+        assert!(tree.test_invariants());
+        assert!(tree.are_leaves_depth_3());
+    }
+
+    #[test]
+    fn test_insert_at_left() {
+        let tree = d3tree![
+            0 => [
+                3 => [7, 8],
+                4 => [9],
+            ],
+        ];
+
+        let insert = d3tree![
+            0 => [3 => [1, 2]]
+        ];
+
+        let expected = d3tree![
+            0 => [
+                3 => [7, 8, 1, 2],
+                4 => [9],
+            ],
+        ];
+
+        let res = tree.insert_at(2, Attach::Left, insert).unwrap();
+
+        assert_eq!(res, expected);
+    }
+
+    #[test]
+    fn test_insert_at_right() {
+        let tree = d3tree![
+            0 => [
+                3 => [7, 8],
+                4 => [9],
+            ],
+        ];
+
+        let insert = d3tree![
+            0 => [4 => [1]]
+        ];
+
+        let expected = d3tree![
+            0 => [
+                3 => [7, 8],
+                4 => [1, 9],
+            ],
+        ];
+
+        let res = tree.insert_at(2, Attach::Right, insert).unwrap();
+
+        assert_eq!(res, expected);
+    }
+
+    #[test]
+    fn test_insert_at_both() {
+        let tree = d3tree![
+            0 => [
+                3 => [7, 8],
+                4 => [9],
+            ],
+        ];
+
+        // the boundary between the two nodes of layer 1 is replaced, joining them
+        let insert = d3tree![
+            0 => [5 => [1]]
+        ];
+
+        let expected = d3tree![
+            0 => [5 => [7, 8, 1, 9]],
+        ];
+
+        let res = tree.insert_at(2, Attach::Both, insert).unwrap();
+
+        assert_eq!(res, expected);
+    }
+
+    #[test]
+    fn test_insert_at_edge() {
+        let tree = d3tree![
+            0 => [3 => [7, 8]],
+        ];
+
+        let insert = d3tree![
+            0 => [3 => [1]]
+        ];
+
+        let expected = d3tree![
+            0 => [3 => [1, 7, 8]],
+        ];
+
+        // nothing on the left of the first leaf, attaches to the right
+        let res = tree.insert_at(0, Attach::Left, insert).unwrap();
+
+        assert_eq!(res, expected);
+    }
+}
+
+mod depth5 {
+    use super::Attach;
+    use crate::d5tree;
+
+    #[test]
+    fn test_leaves_depth_5() {
+        let tree = d5tree![
+            0 => [1 => [2 => [3 => [4, 5]], 6 => [7 => [8]]]],
+        ];
+        assert!(tree.are_leaves_depth_5());
+        assert!(tree.test_invariants());
+
+        let tree = d5tree![
+            0 => [1 => [2 => [3 => [4, 5]], 6 => [7 => []]]],
+        ];
+        assert!(!tree.are_leaves_depth_5());
+    }
+
+    #[test]
+    fn test_children() {
+        let tree = d5tree![
+            0 => [1 => [2 => [3 => [4, 5]], 6 => [7 => [8], 9 => [10]]]],
+        ];
+        assert_eq!(tree.children_1(0), 0..1);
+        assert_eq!(tree.children_2(0), 0..2);
+        assert_eq!(tree.children_3(1), 1..3);
+        assert_eq!(tree.children_4(2), 3..4);
+    }
+
+    #[test]
+    fn test_replace_range_across_layer_2() {
+        // replace the last leaf of the first layer 2 node and the first leaf of the second one:
+        // both layer 2 nodes are merged into the layer 2 node of the replacement
+        let tree = d5tree![
+            0 => [1 => [
+                2 => [3 => [4, 5]],
+                6 => [7 => [8, 9]],
+            ]],
+        ];
+        let replace_with = d5tree![
+            10 => [11 => [12 => [13 => [14]]]],
+        ];
+        let expected = d5tree![
+            10 => [11 => [12 => [13 => [4, 14, 9]]]],
+        ];
+
+        let res = tree.replace_range(1..3, replace_with).unwrap();
+        assert_eq!(res, expected);
+    }
+
+    #[test]
+    fn test_replace_range_keeps_other_nodes() {
+        let tree = d5tree![
+            0 => [1 => [
+                2 => [3 => [4], 5 => [6]],
+                7 => [8 => [9]],
+            ]],
+            10 => [11 => [12 => [13 => [14]]]],
+        ];
+        let replace_with = d5tree![
+            20 => [21 => [22 => [23 => [24], 25 => [26]]]],
+        ];
+        let expected = d5tree![
+            20 => [21 => [
+                22 => [3 => [4], 23 => [24], 25 => [26]],
+                7 => [8 => [9]],
+            ]],
+            10 => [11 => [12 => [13 => [14]]]],
+        ];
+
+        let res = tree.replace_range(1..2, replace_with).unwrap();
+        assert_eq!(res.layer_3(), expected.layer_3());
+        assert_eq!(res.layer_4(), expected.layer_4());
+        assert!(res.test_invariants());
+        assert!(res.are_leaves_depth_5());
+    }
+
+    #[test]
+    fn test_insert_at() {
+        let tree = d5tree![
+            0 => [1 => [2 => [3 => [4]], 5 => [6 => [7]]]],
+        ];
+        let insert = d5tree![
+            0 => [1 => [5 => [6 => [8]]]],
+        ];
+        let expected = d5tree![
+            0 => [1 => [2 => [3 => [4]], 5 => [6 => [8, 7]]]],
+        ];
+
+        let res = tree.insert_at(1, Attach::Right, insert).unwrap();
+        assert_eq!(res, expected);
+    }
+
+    #[test]
+    fn test_prune() {
+        let mut tree = d5tree![
+            0 => [1 => [2 => [3 => [4], 5 => []], 6 => [7 => []]]],
+            8 => [9 => [10 => [11 => []]]],
+        ];
+        tree.prune();
+
+        let expected = d5tree![
+            0 => [1 => [2 => [3 => [4]]]],
+        ];
+        assert_eq!(tree, expected);
+    }
 }
 
 #[test]